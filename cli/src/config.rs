@@ -0,0 +1,95 @@
+use std::{collections::HashMap, fs, fs::File, io::Read, path::Path};
+
+use august_plugin_system::{Depend, StdInfo};
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use zip::{result::ZipError, ZipArchive};
+
+use crate::error::ConfigError;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BundleConfig {
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub license: Option<String>,
+    pub depends: Option<HashMap<String, VersionReq>>,
+    pub optional_depends: Option<HashMap<String, VersionReq>>,
+}
+
+impl BundleConfig {
+    // Конфигурация читается из каталога бандла или прямо из архива
+    pub fn load(bundle_path: &Path) -> Result<Self, ConfigError> {
+        let content = match bundle_path.is_dir() {
+            true => {
+                let config_path = bundle_path.join("config.toml");
+                if !config_path.exists() {
+                    return Err(ConfigError::DoesNotContainConfig);
+                }
+
+                fs::read_to_string(config_path)?
+            }
+            false => {
+                let mut archive = ZipArchive::new(File::open(bundle_path)?)?;
+                let mut file = archive.by_name("config.toml").map_err(|e| match e {
+                    ZipError::FileNotFound => ConfigError::DoesNotContainConfig,
+                    e => e.into(),
+                })?;
+
+                let mut content = String::new();
+                file.read_to_string(&mut content)?;
+                content
+            }
+        };
+
+        Ok(toml::from_str(&content)?)
+    }
+
+    pub fn info(&self) -> StdInfo {
+        StdInfo {
+            depends: to_depends(&self.depends),
+            optional_depends: to_depends(&self.optional_depends),
+        }
+    }
+}
+
+// Зависимости сортируются, чтобы вывод не зависел от порядка в HashMap
+fn to_depends(depends: &Option<HashMap<String, VersionReq>>) -> Vec<Depend> {
+    let mut depends: Vec<Depend> = depends.as_ref().map_or(vec![], |depends| {
+        depends
+            .iter()
+            .map(|(id, version)| Depend::new(id.clone(), version.clone()))
+            .collect()
+    });
+    depends.sort_by(|a, b| a.id.cmp(&b.id));
+    depends
+}
+
+#[test]
+fn load_config() {
+    let config = BundleConfig::load(Path::new("../plugins/dependency/dep_2-v1.0.0.vpl")).unwrap();
+    assert_eq!(config.name, "Depend 2");
+
+    let info = config.info();
+    assert_eq!(info.depends[0].to_string(), "dep_1[^1.0.0]");
+    assert_eq!(info.optional_depends[0].id, "dep_3");
+
+    // Архив читается без распаковки
+    let target_path = std::env::temp_dir().join("august_cli_config");
+    std::fs::create_dir_all(&target_path).unwrap();
+    august_plugin_system::utils::archive::zip(
+        "../plugins/dependency/dep_2-v1.0.0.vpl",
+        target_path.to_str().unwrap(),
+        zip::CompressionMethod::Stored,
+        |_| {},
+    )
+    .unwrap();
+
+    let config = BundleConfig::load(&target_path.join("dep_2-v1.0.0.vpl")).unwrap();
+    assert_eq!(config.name, "Depend 2");
+
+    assert!(matches!(
+        BundleConfig::load(Path::new("../plugins/native_plugin")),
+        Err(ConfigError::DoesNotContainConfig)
+    ));
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use august_plugin_system::{
+    utils::{
+        BundleFromError, BundleUnzipError, BundleZipError, LoadPluginError, RegisterPluginError,
+    },
+    Bundle,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Does not contain config")]
+    DoesNotContainConfig,
+    #[error("Failed to read config")]
+    ReadFailed(#[from] std::io::Error),
+    #[error("Failed to read bundle archive")]
+    ArchiveFailed(#[from] zip::result::ZipError),
+    #[error("Invalid config")]
+    ParseFailed(#[from] toml::de::Error),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CommandError {
+    #[error("Path `{0}` is not valid UTF-8")]
+    InvalidPath(PathBuf),
+    #[error("Path `{0}` has no bundle name")]
+    NoName(PathBuf),
+    #[error("Invalid bundle name")]
+    InvalidName(#[from] BundleFromError),
+    #[error("`{0}` already exists")]
+    AlreadyExists(PathBuf),
+    #[error("Failed to pack bundle")]
+    PackFailed(#[from] BundleZipError),
+    #[error("Failed to unpack bundle")]
+    UnpackFailed(#[from] BundleUnzipError),
+    #[error("Failed to read config")]
+    ConfigFailed(#[from] ConfigError),
+    #[error("{0} of {1} bundles are invalid")]
+    InvalidBundles(usize, usize),
+    #[error("Folder does not contain bundles")]
+    NoBundles,
+    #[error("Failed to register plugins")]
+    RegisterFailed(#[from] RegisterPluginError),
+    #[error("Dependencies of {} plugins cannot be resolved", .0.len())]
+    Unresolved(Vec<Bundle>),
+    #[error("Failed to load plugins")]
+    LoadFailed(#[from] LoadPluginError),
+}
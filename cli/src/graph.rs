@@ -0,0 +1,122 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use august_plugin_system::{
+    context::LoadPluginContext, function::FunctionOutput, utils::ManagerResult, Api, Bundle,
+    Loader, Manager, RegisterPluginContext, StdInfo,
+};
+
+use crate::{config::BundleConfig, error::CommandError};
+
+// Менеджер, который только читает конфигурации и запоминает порядок загрузки
+struct ConfigManager {
+    format: String,
+    order: Arc<Mutex<Vec<Bundle>>>,
+}
+
+impl<'a, O: Send + Sync> Manager<'a, O, StdInfo> for ConfigManager {
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn register_plugin(&mut self, context: RegisterPluginContext) -> ManagerResult<StdInfo> {
+        Ok(BundleConfig::load(context.path)?.info())
+    }
+
+    fn load_plugin(
+        &mut self,
+        context: LoadPluginContext<'a, '_, O, StdInfo>,
+        _: Api<O, StdInfo>,
+    ) -> ManagerResult<()> {
+        let bundle = context.plugin().info().bundle.clone();
+        self.order.lock().unwrap().push(bundle);
+        Ok(())
+    }
+}
+
+// Порядок, в котором загрузчик загрузит бандлы из папки
+pub fn load_order(folder: &Path) -> Result<Vec<Bundle>, CommandError> {
+    let mut paths = vec![];
+    let mut formats: Vec<String> = vec![];
+    for entry in std::fs::read_dir(folder).map_err(|_| CommandError::NoBundles)? {
+        let path = entry.map_err(|_| CommandError::NoBundles)?.path();
+        let bundle = match path.file_name().map(Bundle::from_filename) {
+            Some(Ok(bundle)) => bundle,
+            _ => continue,
+        };
+
+        if !formats.contains(&bundle.format) {
+            formats.push(bundle.format);
+        }
+        paths.push(
+            path.to_str()
+                .ok_or_else(|| CommandError::InvalidPath(path.clone()))?
+                .to_string(),
+        );
+    }
+
+    if paths.is_empty() {
+        return Err(CommandError::NoBundles);
+    }
+    paths.sort();
+
+    let order = Arc::new(Mutex::new(vec![]));
+    let mut loader = Loader::<'static, FunctionOutput, StdInfo>::new();
+    for format in formats {
+        loader
+            .register_manager(ConfigManager {
+                format,
+                order: order.clone(),
+            })
+            .unwrap();
+    }
+
+    loader.register_plugins(paths.iter().map(|path| path.as_str()))?;
+
+    let unresolved: Vec<Bundle> = match loader.validate() {
+        Ok(_) => vec![],
+        Err(august_plugin_system::utils::ValidateLoaderError::InvalidPlugins(errors)) => errors
+            .into_iter()
+            .map(|(bundle, e)| {
+                eprintln!("`{bundle}`: {e}");
+                bundle
+            })
+            .collect(),
+    };
+    if !unresolved.is_empty() {
+        return Err(CommandError::Unresolved(unresolved));
+    }
+
+    let bundles: Vec<Bundle> = loader
+        .get_plugins()
+        .iter()
+        .map(|plugin| plugin.info().bundle.clone())
+        .collect();
+    for bundle in bundles.iter() {
+        if !loader.get_plugin_by_bundle(bundle).unwrap().is_load() {
+            loader.load_plugin_by_bundle(bundle)?;
+        }
+    }
+
+    let order = order.lock().unwrap().clone();
+    Ok(order)
+}
+
+#[test]
+fn dependency_order() {
+    let order = load_order(Path::new("../plugins/dependency")).unwrap();
+    let position = |id: &str| order.iter().position(|bundle| bundle.id == id).unwrap();
+
+    assert_eq!(order.len(), 4);
+    assert!(position("dep_1") < position("dep_2"));
+}
+
+#[test]
+fn cycle_is_unresolved() {
+    assert!(matches!(
+        load_order(Path::new("../plugins/cycle")),
+        Err(CommandError::Unresolved(_))
+    ));
+}
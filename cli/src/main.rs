@@ -0,0 +1,207 @@
+mod config;
+mod error;
+mod graph;
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use august_plugin_system::{
+    utils::archive::{unzip, zip},
+    Bundle,
+};
+use clap::{Parser, Subcommand};
+use zip::CompressionMethod;
+
+use config::BundleConfig;
+use error::CommandError;
+
+#[derive(Parser)]
+#[command(name = "august", version, about = "Tools for August plugin bundles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Packs a bundle directory into an archive
+    Pack {
+        /// Bundle directory, e.g. `my_plugin-v1.0.0.vpl`
+        bundle: PathBuf,
+        /// Directory to put the archive in
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+        /// Store files without compression
+        #[arg(long)]
+        stored: bool,
+    },
+    /// Unpacks a bundle archive into a directory
+    Unpack {
+        /// Bundle archive
+        bundle: PathBuf,
+        /// Directory to unpack the bundle into
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Prints the bundle name and its config
+    Inspect {
+        /// Bundle directory or archive
+        bundle: PathBuf,
+    },
+    /// Checks bundle names and configs
+    Validate {
+        /// Bundle directories or archives
+        #[arg(required = true)]
+        bundles: Vec<PathBuf>,
+    },
+    /// Resolves a folder of bundles and prints the load order
+    Graph {
+        /// Folder with bundles
+        folder: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Pack {
+            bundle,
+            output,
+            stored,
+        } => pack(&bundle, &output, stored),
+        Command::Unpack { bundle, output } => unpack(&bundle, &output),
+        Command::Inspect { bundle } => inspect(&bundle),
+        Command::Validate { bundles } => validate(&bundles),
+        Command::Graph { folder } => graph(&folder),
+    };
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            print_error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_error(e: &dyn Error) {
+    eprint!("error: {e}");
+
+    let mut source = e.source();
+    while let Some(e) = source {
+        eprint!(": {e}");
+        source = e.source();
+    }
+    eprintln!();
+}
+
+fn to_str(path: &Path) -> Result<&str, CommandError> {
+    path.to_str()
+        .ok_or_else(|| CommandError::InvalidPath(path.to_path_buf()))
+}
+
+// Имя бандла берётся из последнего компонента пути
+fn bundle_name(path: &Path) -> Result<Bundle, CommandError> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| CommandError::NoName(path.to_path_buf()))?;
+    Ok(Bundle::from_filename(name)?)
+}
+
+fn pack(bundle: &Path, output: &Path, stored: bool) -> Result<(), CommandError> {
+    bundle_name(bundle)?;
+
+    // zip молча пропускает существующий архив, поэтому проверяем заранее
+    let target = output.join(bundle.file_name().unwrap());
+    if target.exists() {
+        return Err(CommandError::AlreadyExists(target));
+    }
+
+    let method = match stored {
+        true => CompressionMethod::Stored,
+        false => CompressionMethod::Deflated,
+    };
+    zip(bundle, to_str(output)?, method, |name| {
+        if !name.as_os_str().is_empty() {
+            println!("  {}", name.display());
+        }
+    })?;
+
+    println!("Packed `{}`", target.display());
+    Ok(())
+}
+
+fn unpack(bundle: &Path, output: &Path) -> Result<(), CommandError> {
+    let target = output.join(bundle_name(bundle)?.to_string());
+    if target.exists() {
+        return Err(CommandError::AlreadyExists(target));
+    }
+
+    unzip(bundle, to_str(output)?)?;
+
+    println!("Unpacked `{}`", target.display());
+    Ok(())
+}
+
+fn inspect(bundle: &Path) -> Result<(), CommandError> {
+    let name = bundle_name(bundle)?;
+    let config = BundleConfig::load(bundle)?;
+    let info = config.info();
+
+    println!("id: {}", name.id);
+    println!("version: {}", name.version);
+    println!("format: {}", name.format);
+    println!("name: {}", config.name);
+    println!("description: {}", config.description);
+    println!("author: {}", config.author);
+    if let Some(license) = config.license {
+        println!("license: {license}");
+    }
+
+    println!("depends:");
+    for depend in info.depends {
+        println!("  {depend}");
+    }
+    println!("optional depends:");
+    for depend in info.optional_depends {
+        println!("  {depend}");
+    }
+
+    Ok(())
+}
+
+fn validate(bundles: &[PathBuf]) -> Result<(), CommandError> {
+    let mut invalid = 0;
+    for bundle in bundles {
+        let result = bundle_name(bundle)
+            .and_then(|_| BundleConfig::load(bundle).map_err(CommandError::from));
+
+        match result {
+            Ok(_) => println!("ok: {}", bundle.display()),
+            Err(e) => {
+                invalid += 1;
+                eprint!("{}: ", bundle.display());
+                print_error(&e);
+            }
+        }
+    }
+
+    match invalid {
+        0 => Ok(()),
+        invalid => Err(CommandError::InvalidBundles(invalid, bundles.len())),
+    }
+}
+
+fn graph(folder: &Path) -> Result<(), CommandError> {
+    let order = graph::load_order(folder)?;
+
+    for (index, bundle) in order.iter().enumerate() {
+        println!("{}. {bundle}", index + 1);
+    }
+
+    Ok(())
+}
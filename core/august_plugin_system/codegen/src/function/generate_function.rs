@@ -1,13 +1,14 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use syn::{ReturnType, Type, TypePath};
+use syn::{GenericArgument, PathArguments, ReturnType, Type, TypePath};
 
-use crate::function::utils::{clear_ref, get_literal_type};
+use crate::function::utils::{clear_ref, get_literal_type, is_serde};
 
 pub(crate) fn generate_function(
     externals: &Vec<(Ident, &Type)>,
     inputs: &Vec<(Ident, &Type)>,
     output: &ReturnType,
+    coercion: Option<TokenStream>,
     args: TokenStream,
     block: TokenStream,
 ) -> TokenStream {
@@ -15,14 +16,29 @@ pub(crate) fn generate_function(
     let ins = generate_inputs(inputs);
     let call = function_call(exts, ins, output);
     let out = return_output(output);
+    let coerce = generate_coercion(coercion);
 
     quote! {
+        #coerce
         let func = move |#args| #output #block;
 		#call
 		#out
     }
 }
 
+// Приведение аргументов к типам параметров перед разбором
+fn generate_coercion(coercion: Option<TokenStream>) -> Option<TokenStream> {
+    coercion.map(|policy| {
+        quote! {
+            let args = args
+                .iter()
+                .zip(august_plugin_system::function::Function::inputs(self))
+                .map(|(var, arg)| var.coerce_to_with(&arg.ty, #policy))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+    })
+}
+
 fn generate_exts(externals: &Vec<(Ident, &Type)>) -> TokenStream {
     let exts: Vec<TokenStream> = externals.iter()
         .map(|(name, _)| {
@@ -39,7 +55,20 @@ fn generate_inputs(inputs: &Vec<(Ident, &Type)>) -> TokenStream {
         .enumerate()
         .map(|(index, (_, ty))| {
             let ty = clear_ref(*ty);
-            quote! { args[#index].try_parse_ref::<#ty>()? }
+            if is_serde(&ty) {
+                return quote! {
+                    &august_plugin_system::variable::from_variable::<#ty>(
+                        args.get(#index).cloned().unwrap_or_default()
+                    )?
+                };
+            }
+
+            // Недостающий аргумент считается `Null`
+            quote! {
+                args.get(#index)
+                    .unwrap_or(&august_plugin_system::variable::Variable::Null)
+                    .try_parse_ref::<#ty>()?
+            }
         })
         .collect();
 
@@ -89,9 +118,25 @@ fn serialize_output(ty: &TypePath) -> TokenStream {
         quote! { august_plugin_system::variable::Variable::#token (result) }
     } else if type_name == "Vec" {
         quote! { august_plugin_system::variable::Variable::List(result.into_iter().map(|item| item.into()).collect()) }
+    } else if type_name == "HashMap" || type_name == "BTreeMap" {
+        quote! { august_plugin_system::variable::Variable::Map(result.into_iter().map(|(key, item)| (key, item.into())).collect()) }
+    } else if type_name == "Option" {
+        let inner = match &ty.path.segments.last().unwrap().arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(inner)) => serialize_output(get_literal_type(inner)),
+                _ => TokenStream::new(),
+            },
+            _ => TokenStream::new(),
+        };
+        quote! {
+            match result {
+                Some(result) => #inner,
+                None => august_plugin_system::variable::Variable::Null,
+            }
+        }
     } else if type_name == "Variable" {
         quote! { result }
     } else {
-        TokenStream::new()
+        quote! { august_plugin_system::variable::to_variable(&result)? }
     }
 }
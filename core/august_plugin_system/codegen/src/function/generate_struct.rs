@@ -1,10 +1,14 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Error, ItemFn, Result, ReturnType, Signature, Type, TypePath};
+use syn::{
+    Error, GenericArgument, ItemFn, PathArguments, Result, ReturnType, Signature, Type, TypePath,
+};
 
 use super::{
     generate_function::generate_function,
-    utils::{get_attributes, get_inputs, get_literal_type},
+    utils::{
+        get_attributes, get_doc_summary, get_docs, get_inputs, get_literal_type, is_bytes, is_serde,
+    },
 };
 
 pub(crate) fn generate_struct(
@@ -20,19 +24,21 @@ pub(crate) fn generate_struct(
 
     let ins = get_inputs(sig.inputs.iter().skip(1));
 
+    let docs = get_docs(&ast.attrs);
+
     let name = generate_name(attrs.get("name"), &ident.to_string());
-    // //TODO: Внедрить описание функций в August
-    // // let description = generate_description(
-    // //     attrs.get("description"),
-    // //     &"Description is missing".to_string(),
-    // // );
-    let inputs = generate_inputs(&ins)?;
-    let output = generate_output(&sig.output)?;
+    let description = generate_description(attrs.get("description"), &docs);
+    let inputs = generate_inputs(&ins, &docs)?;
+    let output = generate_output(&sig.output, &docs)?;
+    let coercion = generate_coercion(attrs.get("coerce"), attr)?;
+
+    let call_checked = generate_call_checked(coercion.as_ref());
 
     let function = generate_function(
         &exts,
         &ins,
         &sig.output,
+        coercion,
 		ast.sig.inputs.to_token_stream(),
         ast.block.as_ref().to_token_stream(),
     );
@@ -47,6 +53,10 @@ pub(crate) fn generate_struct(
                 #name
             }
 
+            fn description(&self) -> Option<String> {
+                #description
+            }
+
             fn inputs(&self) -> Vec<august_plugin_system::function::Arg> {
                 #inputs
             }
@@ -58,6 +68,8 @@ pub(crate) fn generate_struct(
             fn call(&self, args: &[august_plugin_system::variable::Variable]) -> Self::Output {
                 #function
             }
+
+            #call_checked
         }
     })
 }
@@ -78,38 +90,107 @@ fn generate_name(name: Option<&String>, or: &String) -> TokenStream {
     quote! { #name.to_string() }
 }
 
-//TODO: Внедрить описание функций в August
-// fn generate_description(description: Option<&String>, or: &String) -> TokenStream {
-//     let description = description.map(|x| x.clone()).unwrap_or(or.to_string());
-//     quote! { #description }
-// }
+fn generate_coercion(policy: Option<&String>, attr: &TokenStream) -> Result<Option<TokenStream>> {
+    let policy = match policy.map(|policy| policy.as_str()) {
+        Some("widening") => format_ident!("Widening"),
+        Some("checked") => format_ident!("Checked"),
+        Some("truncating") => format_ident!("Truncating"),
+        Some(_) => {
+            return Err(Error::new_spanned(
+                attr,
+                "attribute `coerce` must be \"widening\", \"checked\" or \"truncating\"",
+            ))
+        }
+        None => return Ok(None),
+    };
+
+    Ok(Some(
+        quote! { august_plugin_system::variable::CoercionPolicy::#policy },
+    ))
+}
+
+// Проверка типов выполняется после приведения аргументов
+fn generate_call_checked(coercion: Option<&TokenStream>) -> Option<TokenStream> {
+    coercion.map(|policy| {
+        quote! {
+            fn call_checked(
+                &self,
+                args: &[august_plugin_system::variable::Variable],
+            ) -> Result<Self::Output, august_plugin_system::utils::CallArgumentsError> {
+                let inputs = august_plugin_system::function::Function::inputs(self);
+                let args = august_plugin_system::function::coerce_arguments(&inputs, args, #policy);
+                august_plugin_system::function::check_arguments(&inputs, &args)?;
+                Ok(august_plugin_system::function::Function::call(self, &args))
+            }
+        }
+    })
+}
+
+// Описание из атрибута или из комментариев `///`
+fn generate_description(description: Option<&String>, docs: &[String]) -> TokenStream {
+    let description = description
+        .cloned()
+        .unwrap_or_else(|| get_doc_summary(docs));
+
+    match description.is_empty() {
+        true => quote! { None },
+        false => quote! { Some(#description.to_string()) },
+    }
+}
+
+// Строки раздела `# <title>` комментариев
+fn get_doc_section<'a>(docs: &'a [String], title: &str) -> Vec<&'a str> {
+    docs.iter()
+        .skip_while(|line| line.trim() != format!("# {title}"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("# "))
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+// Описание аргумента из раздела `# Arguments` вида "* `name` - описание"
+fn get_arg_description(docs: &[String], name: &str) -> Option<String> {
+    get_doc_section(docs, "Arguments")
+        .into_iter()
+        .find_map(|line| {
+            let line = line.trim_start_matches(['*', '-']).trim_start();
+            let description = line.strip_prefix(&format!("`{name}`"))?;
+            Some(description.trim_start_matches([' ', '-', ':']).to_string())
+        })
+}
 
-fn generate_inputs(inputs: &Vec<(Ident, &Type)>) -> Result<TokenStream> {
+fn generate_inputs(inputs: &[(Ident, &Type)], docs: &[String]) -> Result<TokenStream> {
     let mut result = vec![];
 
     for (name, ty) in inputs {
-        result.push(generate_arg(&name.to_string(), *ty)?);
+        let description = get_arg_description(docs, &name.to_string());
+        result.push(generate_arg(&name.to_string(), *ty, description)?);
     }
 
     Ok(quote! { vec![#(#result),*] })
 }
 
-fn generate_output(output: &ReturnType) -> Result<TokenStream> {
+fn generate_output(output: &ReturnType, docs: &[String]) -> Result<TokenStream> {
     match output {
         syn::ReturnType::Default => Ok(quote! { None }),
         syn::ReturnType::Type(_, ty) => {
-            let arg = generate_arg(&"output".to_string(), &*ty)?;
+            // Описание результата - раздел `# Returns`
+            let description = Some(get_doc_section(docs, "Returns").join("\n"))
+                .filter(|description| !description.is_empty());
+            let arg = generate_arg(&"output".to_string(), &*ty, description)?;
             Ok(quote! { Some(#arg) })
         }
     }
 }
 
-fn generate_arg(name: &String, ty: &Type) -> Result<TokenStream> {
-    let ty = get_variable_type_path(get_literal_type(ty))?;
-    Ok(quote! { august_plugin_system::function::Arg::new(#name, #ty) })
+fn generate_arg(name: &String, ty: &Type, description: Option<String>) -> Result<TokenStream> {
+    let ty = get_variable_type(ty)?;
+    let description = description.map(|description| quote! { .with_description(#description) });
+    Ok(quote! { august_plugin_system::function::Arg::new(#name, #ty) #description })
 }
 
-const VARIABLE_TYPES: [(&str, &str); 15] = [
+const VARIABLE_TYPES: [(&str, &str); 16] = [
     ("i8", "I8"),
     ("i16", "I16"),
     ("i32", "I32"),
@@ -123,10 +204,45 @@ const VARIABLE_TYPES: [(&str, &str); 15] = [
     ("bool", "Bool"),
     ("char", "Char"),
     ("String", "String"),
-    ("Vec", "List"),
+    ("HashMap", "Map"),
+    ("BTreeMap", "Map"),
     ("Variable", "Let"),
 ];
 
+pub(crate) fn get_variable_type(ty: &Type) -> Result<TokenStream> {
+    if is_bytes(ty) {
+        return Ok(quote! { august_plugin_system::variable::VariableType::Bytes });
+    }
+
+    let path = get_literal_type(ty);
+    if is_serde(&Type::Path(path.clone())) {
+        return Ok(quote! { august_plugin_system::variable::VariableType::Let });
+    }
+
+    let segment = path.path.segments.last().unwrap();
+
+    // Тип списка включает тип его элементов, необязательный - тип значения
+    let wrapper = match segment.ident.to_string().as_str() {
+        "Vec" => Some(format_ident!("list")),
+        "Option" => Some(format_ident!("optional")),
+        _ => None,
+    };
+    if let Some(wrapper) = wrapper {
+        return match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(ty)) => {
+                    let ty = get_variable_type(ty)?;
+                    Ok(quote! { august_plugin_system::variable::VariableType::#wrapper(#ty) })
+                }
+                _ => Err(Error::new_spanned(args, "type must contain only a type")),
+            },
+            _ => Err(Error::new_spanned(path, "type must contain only a type")),
+        };
+    }
+
+    get_variable_type_path(path)
+}
+
 fn get_variable_type_path(path: &TypePath) -> Result<TokenStream> {
     let ident = path.path.segments.last().unwrap().ident.to_string();
 
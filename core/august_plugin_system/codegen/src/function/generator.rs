@@ -13,8 +13,10 @@ pub(crate) fn generate(ast: &ItemFn, attr: &TokenStream) -> Result<TokenStream>
     let externals = generate_externals(&exts);
 
     let structure = generate_struct(ast, sig, ident, attr, &exts)?;
+    let docs = ast.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
 
     Ok(quote! {
+        #(#docs)*
         pub fn #ident(#exts_args) -> impl august_plugin_system::function::Function<Output = august_plugin_system::function::FunctionOutput> {
             #structure
 
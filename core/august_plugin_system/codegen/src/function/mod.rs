@@ -3,9 +3,9 @@ use syn::{Result, ItemFn};
 
 mod generate_function;
 mod generator;
-mod utils;
-mod generate_struct;
-mod validator;
+pub(crate) mod utils;
+pub(crate) mod generate_struct;
+pub(crate) mod validator;
 
 pub fn derive(ast: ItemFn, attr: TokenStream) -> Result<TokenStream> {
     validator::validate(&ast, &attr)?;
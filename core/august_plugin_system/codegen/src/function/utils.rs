@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
 use proc_macro2::{Ident, Span, TokenStream};
-use syn::{Error, FnArg, Pat, Result, Type, TypePath};
+use quote::ToTokens;
+use syn::{
+    parse::Parser, punctuated::Punctuated, Attribute, Error, Expr, ExprLit, FnArg, Lit, Meta,
+    MetaNameValue, Pat, Result, Token, Type, TypePath,
+};
 
 pub(crate) fn get_literal_type(ty: &Type) -> &TypePath {
     match ty {
@@ -14,20 +18,87 @@ pub(crate) fn get_literal_type(ty: &Type) -> &TypePath {
     }
 }
 
-pub(crate) fn get_attributes(attr: &TokenStream) -> HashMap<String, String> {
-    let attrs_str = attr.to_string();
-    match attrs_str.is_empty() {
-        true => HashMap::new(),
-        false => attrs_str
-            .split(',')
-            .map(|attr| {
-                let attr: Vec<&str> = attr.split('=').map(|token| token.trim()).collect();
-                (attr[0].to_string(), attr[1].trim_matches('"').to_string())
-            })
-            .collect(),
+// Байты передаются срезом `&[u8]`
+pub(crate) fn is_bytes(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) if r.mutability.is_none() => match &*r.elem {
+            Type::Slice(slice) => match &*slice.elem {
+                Type::Path(path) => path.path.is_ident("u8"),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
     }
 }
 
+// Через serde передаются только типы в обёртке `Serde<T>`
+pub(crate) fn is_serde(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Serde"),
+        _ => false,
+    }
+}
+
+pub(crate) fn parse_attributes(attr: &TokenStream) -> Result<Punctuated<MetaNameValue, Token![,]>> {
+    Punctuated::parse_terminated.parse2(attr.clone())
+}
+
+pub(crate) fn get_attributes(attr: &TokenStream) -> HashMap<String, String> {
+    parse_attributes(attr)
+        .unwrap()
+        .into_iter()
+        .filter_map(|meta| match meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(data),
+                ..
+            }) => Some((meta.path.to_token_stream().to_string(), data.value())),
+            _ => None,
+        })
+        .collect()
+}
+
+// Строки комментариев `///`
+pub(crate) fn get_docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.lines()
+                .map(|line| {
+                    line.strip_prefix(' ')
+                        .unwrap_or(line)
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Описание - комментарии до первого раздела `# <title>`
+pub(crate) fn get_doc_summary(docs: &[String]) -> String {
+    docs.iter()
+        .take_while(|line| !line.starts_with("# "))
+        .map(|line| line.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
 pub(crate) fn get_externals(arg: &FnArg) -> Vec<(Ident, &Type)> {
     match arg {
         FnArg::Receiver(_) => panic!("Receiver is not supported"),
@@ -108,6 +179,7 @@ pub(crate) fn clear_ref(ty: &Type) -> Type {
         }
         Type::Reference(r) => match &*r.elem {
             Type::Path(path) => Type::Path(path.clone()),
+            Type::Slice(slice) if is_bytes(ty) => Type::Slice(slice.clone()),
             _ => panic!("Wrong type"),
         },
         _ => panic!("Wrong type"),
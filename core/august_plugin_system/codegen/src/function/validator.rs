@@ -1,9 +1,11 @@
 use proc_macro2::TokenStream;
+use quote::ToTokens;
 use syn::{
-    Error, FnArg, GenericArgument, ItemFn, Pat, PathArguments, Result, Signature, Type, TypePath,
+    Error, Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, Pat, PathArguments, Result,
+    Signature, Type, TypePath,
 };
 
-use super::utils::pat_to_ident;
+use super::utils::{is_bytes, is_serde, parse_attributes, pat_to_ident};
 
 pub(crate) fn validate(ast: &ItemFn, attr: &TokenStream) -> Result<()> {
     if !ast.sig.generics.params.is_empty() {
@@ -14,44 +16,35 @@ pub(crate) fn validate(ast: &ItemFn, attr: &TokenStream) -> Result<()> {
     validate_function(&ast.sig)
 }
 
-//TODO: Внедрить описание функций в August
-// const VALIDATE_ATTRIBUTES: [&str; 2] = ["name", "description"];
-// const VALIDATE_STRING_ATTRIBUTES: [&str; 2] = ["name", "description"];
-
-const VALIDATE_ATTRIBUTES: [&str; 1] = ["name"];
-const VALIDATE_STRING_ATTRIBUTES: [&str; 1] = ["name"];
+const VALIDATE_ATTRIBUTES: [&str; 3] = ["name", "description", "coerce"];
+const VALIDATE_STRING_ATTRIBUTES: [&str; 3] = ["name", "description", "coerce"];
 
 fn validate_attributes(attrs: &TokenStream) -> Result<()> {
-    let attrs_str = attrs.to_string();
-    if !attrs_str.is_empty() {
-        for attr in attrs_str.split(',') {
-            let attr: Vec<&str> = attr.split('=').map(|token| token.trim()).collect();
-
-            if attr.len() != 2 {
-                return Err(Error::new_spanned(
-                    attrs,
-                    "attributes must have the format `path = data`",
-                ));
-            }
+    let metas = parse_attributes(attrs)
+        .map_err(|e| Error::new(e.span(), "attributes must have the format `path = data`"))?;
 
-            let path = attr[0];
+    for meta in metas {
+        let path = meta.path.to_token_stream().to_string();
 
-            if !VALIDATE_ATTRIBUTES.iter().any(|attr| *attr == path) {
-                return Err(Error::new_spanned(
-                    attrs,
-                    format!("attribute `{}` does not exist", path),
-                ));
-            }
+        if !VALIDATE_ATTRIBUTES.iter().any(|attr| *attr == path) {
+            return Err(Error::new_spanned(
+                &meta.path,
+                format!("attribute `{}` does not exist", path),
+            ));
+        }
 
-            if VALIDATE_STRING_ATTRIBUTES.iter().any(|attr| *attr == path) {
-                let data: Vec<char> = attr[1].chars().collect();
-                if data.first() != Some(&'"') || data.last() != Some(&'"') {
-                    return Err(Error::new_spanned(
-                        attrs,
-                        format!("attribute `{}` must contain string", path),
-                    ));
-                }
-            }
+        let is_string = matches!(
+            meta.value,
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(_),
+                ..
+            })
+        );
+        if VALIDATE_STRING_ATTRIBUTES.iter().any(|attr| *attr == path) && !is_string {
+            return Err(Error::new_spanned(
+                &meta.value,
+                format!("attribute `{}` must contain string", path),
+            ));
         }
     }
 
@@ -63,7 +56,9 @@ fn validate_function(sig: &Signature) -> Result<()> {
     validate_args(sig.inputs.iter().skip(1))?;
 
     if let syn::ReturnType::Type(_, ref ty) = sig.output {
-        validate_type(ty.as_ref(), false)?;
+        if !is_serde(ty) {
+            validate_type(ty.as_ref(), false)?;
+        }
     }
 
     Ok(())
@@ -101,13 +96,17 @@ where
 {
     args.try_for_each(|arg| match arg {
         FnArg::Receiver(_) => Err(Error::new_spanned(arg, "Receiver is not supported")),
-        FnArg::Typed(pat) => validate_type(&*pat.ty, true),
+        FnArg::Typed(pat) if is_bytes(&pat.ty) => Ok(()),
+        FnArg::Typed(pat) => match &*pat.ty {
+            Type::Reference(r) if r.mutability.is_none() && is_serde(&r.elem) => Ok(()),
+            ty => validate_type(ty, true),
+        },
     })?;
 
     Ok(())
 }
 
-fn validate_type(ty: &Type, is_ref: bool) -> Result<()> {
+pub(crate) fn validate_type(ty: &Type, is_ref: bool) -> Result<()> {
     match is_ref {
         true => match ty {
             Type::Path(path) => validate_type_path(&path, is_ref),
@@ -133,9 +132,9 @@ fn validate_type(ty: &Type, is_ref: bool) -> Result<()> {
     }
 }
 
-const VALIDATE_TYPE: [&str; 15] = [
+const VALIDATE_TYPE: [&str; 18] = [
     "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f32", "f64", "bool", "char", "String",
-    "Vec", "Variable",
+    "Vec", "Option", "HashMap", "BTreeMap", "Variable",
 ];
 
 fn validate_type_path(path: &TypePath, is_ref: bool) -> Result<()> {
@@ -143,17 +142,39 @@ fn validate_type_path(path: &TypePath, is_ref: bool) -> Result<()> {
     let ty = segment.ident.to_string();
 
     if VALIDATE_TYPE.contains(&ty.as_str()) {
-        if ty == "Vec" {
+        if ty == "Vec" || ty == "Option" {
             match &segment.arguments {
                 PathArguments::AngleBracketed(args) => {
                     let arg = args.args.first().unwrap();
                     match arg {
                         GenericArgument::Type(ty) => return validate_type(ty, is_ref),
-                        _ => return Err(Error::new_spanned(arg, "Vec must contain only a type")),
+                        _ => {
+                            return Err(Error::new_spanned(
+                                arg,
+                                format!("{ty} must contain only a type"),
+                            ))
+                        }
                     }
                 }
                 _ => (),
             }
+        } else if ty == "HashMap" || ty == "BTreeMap" {
+            if is_ref {
+                return Err(Error::new_spanned(
+                    path,
+                    "type must contain only references (&T) or Vec<&T>",
+                ));
+            }
+
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                match (args.args.first(), args.args.last()) {
+                    (
+                        Some(GenericArgument::Type(Type::Path(key))),
+                        Some(GenericArgument::Type(value)),
+                    ) if key.path.is_ident("String") => return validate_type(value, is_ref),
+                    _ => return Err(Error::new_spanned(args, "map must contain String keys")),
+                }
+            }
         } else if is_ref {
             return Err(Error::new_spanned(
                 path,
@@ -0,0 +1,172 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    Attribute, Error, Expr, ExprLit, FnArg, ImplItem, ImplItemFn, ItemFn, ItemImpl, Lit, Meta,
+    Result, Type,
+};
+
+use crate::function::{
+    self,
+    utils::{parse_attributes, pat_to_ident},
+};
+
+pub fn derive(mut ast: ItemImpl, attr: TokenStream) -> Result<TokenStream> {
+    let name = get_name(attr)?;
+
+    if let Some((_, path, _)) = &ast.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "trait implementations are not supported",
+        ));
+    }
+
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &ast.generics,
+            "generics are not supported",
+        ));
+    }
+
+    let self_ty = ast.self_ty.as_ref().clone();
+    let ident = match &self_ty {
+        Type::Path(path) => path.path.segments.last().unwrap().ident.clone(),
+        ty => return Err(Error::new_spanned(ty, "type is not supported")),
+    };
+    let module = format_ident!("__{}_{}", ident.to_string().to_lowercase(), name);
+
+    let mut idents = vec![];
+    let mut functions = vec![];
+    for item in ast.items.iter_mut() {
+        if let ImplItem::Fn(method) = item {
+            let attr = take_function_attribute(&mut method.attrs)?;
+
+            // Методы без `self` не становятся функциями
+            let receiver = match method.sig.receiver() {
+                Some(receiver) => receiver,
+                None => continue,
+            };
+            if receiver.reference.is_none() || receiver.mutability.is_some() {
+                return Err(Error::new_spanned(receiver, "method must take `&self`"));
+            }
+
+            let ast = method_to_function(&self_ty, method)?;
+            idents.push(method.sig.ident.clone());
+            functions.push(function::derive(ast, attr)?);
+        }
+    }
+
+    Ok(quote! {
+        #ast
+
+        impl #self_ty {
+            pub fn #name(
+                service: &std::sync::Arc<Self>,
+            ) -> august_plugin_system::Registry<august_plugin_system::function::FunctionOutput> {
+                vec![#(
+                    std::sync::Arc::new(#module::#idents(service.clone()))
+                        as std::sync::Arc<dyn august_plugin_system::function::Function<
+                            Output = august_plugin_system::function::FunctionOutput,
+                        >>
+                ),*]
+            }
+        }
+
+        #[doc(hidden)]
+        mod #module {
+            use super::*;
+
+            #(#functions)*
+        }
+    })
+}
+
+// Имя метода, возвращающего функции. Несколько блоков `impl` одного типа
+// должны называть его по-разному: `#[functions(name = "...")]`
+fn get_name(attr: TokenStream) -> Result<Ident> {
+    let metas = parse_attributes(&attr).map_err(|e| {
+        Error::new(
+            e.span(),
+            "attributes must have the format `#[functions(name = \"...\")]`",
+        )
+    })?;
+
+    let mut name = format_ident!("functions");
+    for meta in metas {
+        if !meta.path.is_ident("name") {
+            return Err(Error::new_spanned(
+                &meta.path,
+                format!("attribute `{}` does not exist", meta.path.to_token_stream()),
+            ));
+        }
+
+        name = match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => value
+                .parse()
+                .map_err(|_| Error::new_spanned(value, "name must be an identifier"))?,
+            value => {
+                return Err(Error::new_spanned(
+                    value,
+                    "attribute `name` must contain string",
+                ))
+            }
+        };
+    }
+
+    Ok(name)
+}
+
+// Атрибут `#[function(...)]` метода передаётся генератору функции
+fn take_function_attribute(attrs: &mut Vec<Attribute>) -> Result<TokenStream> {
+    let index = match attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("function"))
+    {
+        Some(index) => index,
+        None => return Ok(TokenStream::new()),
+    };
+
+    match attrs.remove(index).meta {
+        Meta::Path(_) => Ok(TokenStream::new()),
+        Meta::List(list) => Ok(list.tokens),
+        meta => Err(Error::new_spanned(
+            meta,
+            "attributes must have the format `#[function(path = data)]`",
+        )),
+    }
+}
+
+// Метод превращается в функцию, которая получает сервис как внешнее значение
+fn method_to_function(self_ty: &Type, method: &ImplItemFn) -> Result<ItemFn> {
+    let sig = &method.sig;
+    let ident = &sig.ident;
+    let output = &sig.output;
+    let docs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"));
+
+    let inputs: Vec<&FnArg> = sig.inputs.iter().skip(1).collect();
+    let args = inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(pat) => match pat_to_ident(&pat.pat) {
+                Ok(Some(name)) => Ok(name),
+                _ => Err(Error::new_spanned(
+                    &pat.pat,
+                    "name of the argument is specified incorrectly",
+                )),
+            },
+            FnArg::Receiver(_) => Err(Error::new_spanned(arg, "Receiver is not supported")),
+        })
+        .collect::<Result<Vec<Ident>>>()?;
+
+    syn::parse2(quote! {
+        #(#docs)*
+        fn #ident(__service: &std::sync::Arc<#self_ty>, #(#inputs),*) #output {
+            __service.#ident(#(#args),*)
+        }
+    })
+}
@@ -1,7 +1,9 @@
 mod function;
+mod functions;
+mod requests;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, ItemFn};
+use syn::{parse_macro_input, ItemFn, ItemImpl, ItemTrait};
 
 #[proc_macro_attribute]
 pub fn function(attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -10,4 +12,21 @@ pub fn function(attr: TokenStream, input: TokenStream) -> TokenStream {
 	function::derive(ast, attr.into())
         .unwrap_or_else(|e| e.to_compile_error())
         .into()
-}
\ No newline at end of file
+}
+#[proc_macro_attribute]
+pub fn functions(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as ItemImpl);
+
+    functions::derive(ast, attr.into())
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn requests(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as ItemTrait);
+
+    requests::derive(ast, attr.into())
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
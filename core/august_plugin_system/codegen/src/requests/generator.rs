@@ -0,0 +1,162 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{ItemTrait, Result, ReturnType, Signature, TraitItem, TraitItemFn, Type};
+
+use crate::function::{
+    generate_struct::get_variable_type,
+    utils::{get_doc_summary, get_docs, get_inputs, get_literal_type, is_serde},
+};
+
+pub(crate) fn generate(ast: &ItemTrait) -> Result<TokenStream> {
+    let vis = &ast.vis;
+    let ident = &ast.ident;
+    let companion = format_ident!("{}Requests", ident);
+    let caller = format_ident!("{}Caller", ident);
+
+    let methods: Vec<&TraitItemFn> = ast
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect();
+
+    let requests = methods
+        .iter()
+        .map(|method| generate_request(method))
+        .collect::<Result<Vec<_>>>()?;
+    let calls: Vec<TokenStream> = methods
+        .iter()
+        .map(|method| generate_call(&method.sig))
+        .collect();
+
+    // Трейт остаётся без изменений, запросы и вызовы - в сопутствующих типах
+    Ok(quote! {
+        #ast
+
+        #vis struct #companion;
+
+        impl #companion {
+            pub fn requests() -> Vec<august_plugin_system::function::Request> {
+                vec![#(#requests),*]
+            }
+
+            pub fn caller<'l, 'a, I: august_plugin_system::Info>(
+                loader: &'l august_plugin_system::Loader<'a, august_plugin_system::function::FunctionOutput, I>,
+            ) -> #caller<'l, 'a, I> {
+                #caller { loader }
+            }
+        }
+
+        #vis struct #caller<'l, 'a, I: august_plugin_system::Info> {
+            loader: &'l august_plugin_system::Loader<'a, august_plugin_system::function::FunctionOutput, I>,
+        }
+
+        impl<'l, 'a, I: august_plugin_system::Info> #caller<'l, 'a, I> {
+            #(#calls)*
+        }
+    })
+}
+
+fn generate_request(method: &TraitItemFn) -> Result<TokenStream> {
+    let sig = &method.sig;
+    let name = sig.ident.to_string();
+
+    let inputs = get_inputs(sig.inputs.iter().skip(1))
+        .into_iter()
+        .map(|(_, ty)| get_variable_type(ty))
+        .collect::<Result<Vec<_>>>()?;
+    let output = match &sig.output {
+        ReturnType::Default => quote! { None },
+        ReturnType::Type(_, ty) => {
+            let ty = get_variable_type(ty)?;
+            quote! { Some(#ty) }
+        }
+    };
+
+    let description = get_doc_summary(&get_docs(&method.attrs));
+    let description = match description.is_empty() {
+        true => None,
+        false => Some(quote! { .with_description(#description) }),
+    };
+
+    Ok(quote! {
+        august_plugin_system::function::Request::new(#name, vec![#(#inputs),*], #output) #description
+    })
+}
+
+fn generate_call(sig: &Signature) -> TokenStream {
+    let ident = &sig.ident;
+    let name = ident.to_string();
+
+    let inputs = get_inputs(sig.inputs.iter().skip(1));
+    let params = inputs.iter().map(|(name, ty)| quote! { #name: #ty });
+    let args = inputs.iter().map(|(name, ty)| serialize_arg(name, ty));
+
+    let call = quote! {
+        let args: Vec<august_plugin_system::variable::Variable> = vec![#(#args),*];
+        let outputs = self.loader.call_request(#name, &args)?;
+    };
+
+    match &sig.output {
+        ReturnType::Default => quote! {
+            pub fn #ident(&self, #(#params),*) -> Result<(), august_plugin_system::utils::TypedCallRequestError> {
+                #call
+                for output in outputs {
+                    output.map_err(august_plugin_system::utils::TypedCallRequestError::Plugin)?;
+                }
+                Ok(())
+            }
+        },
+        ReturnType::Type(_, ty) => {
+            let output = deserialize_output(ty);
+            quote! {
+                pub fn #ident(&self, #(#params),*) -> Result<Vec<#ty>, august_plugin_system::utils::TypedCallRequestError> {
+                    #call
+                    outputs
+                        .into_iter()
+                        .map(|output| {
+                            let output = output.map_err(august_plugin_system::utils::TypedCallRequestError::Plugin)?;
+                            #output
+                        })
+                        .collect()
+                }
+            }
+        }
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    get_literal_type(ty)
+        .path
+        .segments
+        .last()
+        .unwrap()
+        .ident
+        .to_string()
+}
+
+fn serialize_arg(name: &Ident, ty: &Type) -> TokenStream {
+    match type_name(ty).as_str() {
+        "Variable" => quote! { #name },
+        _ if is_serde(ty) => quote! { august_plugin_system::variable::to_variable(&#name)? },
+        _ => quote! { august_plugin_system::variable::Variable::from(#name) },
+    }
+}
+
+// Необязательный результат может отсутствовать, остальные - обязательны
+fn deserialize_output(ty: &Type) -> TokenStream {
+    match type_name(ty).as_str() {
+        "Variable" => quote! {
+            output.ok_or(august_plugin_system::utils::TypedCallRequestError::MissingOutput)
+        },
+        "Option" => quote! {
+            Ok(august_plugin_system::variable::from_variable::<#ty>(output.unwrap_or_default())?)
+        },
+        _ => quote! {
+            let output = output.ok_or(august_plugin_system::utils::TypedCallRequestError::MissingOutput)?;
+            Ok(august_plugin_system::variable::from_variable::<#ty>(output)?)
+        },
+    }
+}
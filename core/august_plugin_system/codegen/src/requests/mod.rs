@@ -0,0 +1,10 @@
+use proc_macro2::TokenStream;
+use syn::{ItemTrait, Result};
+
+mod generator;
+mod validator;
+
+pub fn derive(ast: ItemTrait, attr: TokenStream) -> Result<TokenStream> {
+    validator::validate(&ast, &attr)?;
+    generator::generate(&ast)
+}
@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream;
+use syn::{Error, FnArg, ItemTrait, Result, Signature, TraitItem, Type};
+
+use crate::function::{
+    utils::{is_serde, pat_to_ident},
+    validator::validate_type,
+};
+
+pub(crate) fn validate(ast: &ItemTrait, attr: &TokenStream) -> Result<()> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(attr, "attributes are not supported"));
+    }
+
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &ast.generics,
+            "generics are not supported",
+        ));
+    }
+
+    ast.items.iter().try_for_each(|item| match item {
+        TraitItem::Fn(method) => validate_method(&method.sig),
+        item => Err(Error::new_spanned(item, "trait must contain only methods")),
+    })
+}
+
+fn validate_method(sig: &Signature) -> Result<()> {
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "generics are not supported",
+        ));
+    }
+
+    // Запрос объявляется как метод `&self`
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => return Err(Error::new_spanned(sig, "request must take `&self`")),
+    }
+
+    sig.inputs.iter().skip(1).try_for_each(|arg| match arg {
+        FnArg::Receiver(_) => Err(Error::new_spanned(arg, "Receiver is not supported")),
+        FnArg::Typed(pat) => match pat_to_ident(&pat.pat) {
+            Ok(Some(_)) => validate_value_type(&pat.ty),
+            _ => Err(Error::new_spanned(
+                &pat.pat,
+                "name of the argument is specified incorrectly",
+            )),
+        },
+    })?;
+
+    match &sig.output {
+        syn::ReturnType::Type(_, ty) => validate_value_type(ty),
+        syn::ReturnType::Default => Ok(()),
+    }
+}
+
+// Аргументы и результат запроса передаются по значению
+fn validate_value_type(ty: &Type) -> Result<()> {
+    match is_serde(ty) {
+        true => Ok(()),
+        false => validate_type(ty, false),
+    }
+}
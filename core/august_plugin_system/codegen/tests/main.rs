@@ -1,12 +1,27 @@
 #[cfg(test)]
 mod main {
-    use august_plugin_system::{function::Function, function_call, variable::Variable};
+    use std::sync::Arc;
+
+    use august_plugin_system::{
+        function::Function,
+        function_call,
+        variable::{Variable, VariableType},
+    };
 
     extern crate august_plugin_system;
 
     mod functions {
-        use august_plugin_system::variable::Variable;
-        use codegen::function;
+        use std::collections::BTreeMap;
+
+        use august_plugin_system::variable::{Serde, Variable};
+        use codegen::{function, functions};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        pub struct Point {
+            pub x: i32,
+            pub y: i32,
+        }
 
         #[function]
         fn add(_: (), a: Vec<&i32>, b: &String) -> Vec<i32> {
@@ -33,7 +48,86 @@ mod main {
             true.into()
         }
 
-        #[function(name = "Logging")]
+        #[function]
+        fn count(_: (), words: Vec<&String>) -> BTreeMap<String, u32> {
+            let mut counts = BTreeMap::new();
+            for word in words {
+                *counts.entry(word.clone()).or_insert(0) += 1;
+            }
+            counts
+        }
+
+        #[function]
+        fn checksum(_: (), data: &[u8]) -> u32 {
+            data.iter().map(|b| *b as u32).sum()
+        }
+
+        #[function]
+        fn find(_: (), strs: Vec<&String>, pattern: Option<&String>) -> Option<i32> {
+            let pattern = pattern?;
+            strs.iter()
+                .position(|s| s.contains(pattern.as_str()))
+                .map(|index| index as i32)
+        }
+
+        #[function(coerce = "checked")]
+        fn mul(_: (), a: &i32, b: &i32) -> i32 {
+            a * b
+        }
+
+        #[function]
+        fn shift(_: (), point: &Serde<Point>, offset: &i32) -> Serde<Point> {
+            Serde(Point {
+                x: point.x + offset,
+                y: point.y + offset,
+            })
+        }
+
+        pub struct Counter {
+            step: i32,
+        }
+
+        #[functions]
+        impl Counter {
+            pub fn new(step: i32) -> Self {
+                Self { step }
+            }
+
+            /// Adds the step to the value
+            pub fn next(&self, value: &i32) -> i32 {
+                value + self.step
+            }
+
+            #[function(name = "Steps")]
+            pub fn steps(&self, values: Vec<&i32>) -> Vec<i32> {
+                values.into_iter().map(|value| self.next(value)).collect()
+            }
+        }
+
+        // Второй блок `impl` того же типа получает своё имя
+        #[functions(name = "scale_functions")]
+        impl Counter {
+            pub fn scale(&self, value: &i32) -> i32 {
+                value * self.step
+            }
+        }
+
+        /// Divides one number by another.
+        ///
+        /// # Arguments
+        ///
+        /// * `a` - Dividend
+        /// * `b` - Divisor, must not be zero
+        ///
+        /// # Returns
+        ///
+        /// Quotient
+        #[function]
+        fn div(_: (), a: &f64, b: &f64) -> f64 {
+            a / b
+        }
+
+        #[function(name = "Logging", description = "Prints a message, with a title")]
         fn log((title, code): (&Option<String>, &i32), message: &String) {
             let title = title.clone().unwrap_or("[INFO]".to_string());
             println!("{title} #{code}: {message}");
@@ -78,10 +172,184 @@ mod main {
         assert_eq!(result.unwrap(), Some(Variable::Null));
     }
 
+    #[test]
+    fn serialize_count() {
+        let count = functions::count();
+        println!("`count` name: {}", count.name());
+
+        let result = function_call!(count, vec!["a", "b", "a"]);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            Some(Variable::Map(
+                [
+                    ("a".to_string(), 2_u32.into()),
+                    ("b".to_string(), 1_u32.into())
+                ]
+                .into()
+            ))
+        );
+    }
+
+    #[test]
+    fn serialize_shift() {
+        let shift = functions::shift();
+        println!("`shift` name: {}", shift.name());
+        assert_eq!(shift.inputs()[0].ty, VariableType::Let);
+
+        let point =
+            Variable::Map([("x".to_string(), 1.into()), ("y".to_string(), 2.into())].into());
+        let result = function_call!(shift, point, 10);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            Some(Variable::Map(
+                [("x".to_string(), 11.into()), ("y".to_string(), 12.into())].into()
+            ))
+        );
+        assert!(function_call!(shift, 1, 10).is_err());
+    }
+
+    #[test]
+    fn serialize_checksum() {
+        let checksum = functions::checksum();
+        println!("`checksum` name: {}", checksum.name());
+        assert_eq!(checksum.inputs()[0].ty, VariableType::Bytes);
+
+        let data: Arc<[u8]> = Arc::from(&[1, 2, 255][..]);
+        let result = function_call!(checksum, data);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(258_u32.into()));
+    }
+
+    #[test]
+    fn serialize_find() {
+        let find = functions::find();
+        println!("`find` name: {}", find.name());
+        assert_eq!(
+            find.inputs()[1].ty,
+            VariableType::optional(VariableType::String)
+        );
+        assert_eq!(
+            find.output().unwrap().ty,
+            VariableType::optional(VariableType::I32)
+        );
+
+        let mut result = function_call!(find, vec!["moon", "sun"], "su");
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(1.into()));
+
+        result = function_call!(find, vec!["moon", "sun"], "star");
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(Variable::Null));
+
+        // Необязательный аргумент можно не передавать
+        result = function_call!(find, vec!["moon", "sun"]);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(Variable::Null));
+    }
+
+    #[test]
+    fn serialize_mul() {
+        let mul = functions::mul();
+        println!("`mul` name: {}", mul.name());
+
+        let mut result = function_call!(mul, 3_i64, 2.0_f64);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(6.into()));
+
+        result = function_call!(mul, 3_i64, 2.5_f64);
+
+        assert!(result.is_err());
+
+        // Проверка типов выполняется после приведения
+        let result = mul.call_checked(&[3_i64.into(), 2.0_f64.into()]).unwrap();
+        assert_eq!(result.unwrap(), Some(6.into()));
+
+        assert!(mul.call_checked(&[3_i64.into(), 2.5_f64.into()]).is_err());
+    }
+
+    #[test]
+    fn serialize_div() {
+        let div = functions::div();
+        println!("`div` name: {}", div.name());
+
+        assert_eq!(
+            div.description(),
+            Some("Divides one number by another.".to_string())
+        );
+        assert_eq!(div.inputs()[0].description, Some("Dividend".to_string()));
+        assert_eq!(
+            div.inputs()[1].description,
+            Some("Divisor, must not be zero".to_string())
+        );
+        assert_eq!(
+            div.output().unwrap().description,
+            Some("Quotient".to_string())
+        );
+
+        let result = function_call!(div, 3.0, 2.0);
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(1.5.into()));
+    }
+
+    #[test]
+    fn serialize_counter() {
+        let counter = Arc::new(functions::Counter::new(2));
+        assert_eq!(counter.next(&1), 3);
+
+        let registry = functions::Counter::functions(&counter);
+        let names: Vec<_> = registry.iter().map(|function| function.name()).collect();
+        assert_eq!(names, vec!["next", "Steps"]);
+        assert_eq!(
+            registry[0].description(),
+            Some("Adds the step to the value".to_string())
+        );
+
+        let next = &registry[0];
+        let result = function_call!(next, 1);
+        assert_eq!(result.unwrap(), Some(3.into()));
+
+        let steps = &registry[1];
+        let result = function_call!(steps, vec![1, 2]);
+        assert_eq!(result.unwrap(), Some(vec![3, 4].into()));
+
+        // Функции разделяют один сервис
+        assert_eq!(Arc::strong_count(&counter), 3);
+    }
+
+    #[test]
+    fn serialize_named_functions() {
+        let counter = Arc::new(functions::Counter::new(3));
+
+        let registry = functions::Counter::scale_functions(&counter);
+        let names: Vec<_> = registry.iter().map(|function| function.name()).collect();
+        assert_eq!(names, vec!["scale"]);
+
+        let scale = &registry[0];
+        let result = function_call!(scale, 2);
+        assert_eq!(result.unwrap(), Some(6.into()));
+
+        assert_eq!(functions::Counter::functions(&counter).len(), 2);
+    }
+
     #[test]
     fn serialize_log() {
         let log = functions::log(Some("[ERROR]".to_string()), 264);
         println!("`log` name: {}", log.name(),);
+        assert_eq!(
+            log.description(),
+            Some("Prints a message, with a title".to_string())
+        );
+        assert_eq!(functions::sub().description(), None);
 
         let mut result = function_call!(log, "It's error");
 
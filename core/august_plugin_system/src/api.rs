@@ -1,40 +1,63 @@
+use std::{collections::HashMap, sync::Arc};
+
 use rayon::prelude::IntoParallelIterator;
 use semver::Version;
 
 use crate::{
     utils::{
-        CallFunctionDependError, LoadPluginError, PluginCallFunctionError, PluginCallRequestError,
-        Ptr, RegisterManagerError, RegisterPluginError, UnloadPluginError, UnregisterManagerError,
-        UnregisterPluginError,
+        CallFunctionDependError, LoadPluginError, LoaderMut, LoaderRef, PluginCallFunctionError,
+        PluginCallRequestError, RegisterManagerError, RegisterPluginError, UnloadPluginError,
+        UnregisterManagerError, UnregisterPluginError,
     },
     variable::Variable,
-    Bundle, Info, Loader, Manager, Plugin, Registry, Requests,
+    Bundle, Info, LoaderEvent, LoaderInner, LoaderState, Manager, Plugin, PluginsMut, Registry,
+    Requests, Subscription,
 };
 
+/// Доступ плагина к загрузчику.
+///
+/// Методы, которым нужен загрузчик, возвращают `LoaderLocked` (или `None`),
+/// если текущий поток уже удерживает его блокировку: во время загрузки
+/// плагина или пока жив страж, полученный из `Api` или `Loader`. Чтение
+/// запрещено только под блокировкой на запись. Изменять загрузчик из
+/// `load_plugin` менеджера или функции плагина, вызванной хостом под
+/// блокировкой, нельзя — такие вызовы следует выполнять позже.
 pub struct Api<O: Send + Sync + 'static, I: Info + 'static> {
-    loader: Ptr<'static, Loader<'static, O, I>>,
+    loader: Arc<LoaderInner<'static, O, I>>,
     plugin: Bundle,
     depends: Vec<Bundle>,
     optional_depends: Vec<Bundle>,
+    // Функции зависимостей на момент загрузки плагина
+    functions: HashMap<Bundle, Registry<O>>,
 }
 
 impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     pub(crate) const fn new(
-        loader: Ptr<'static, Loader<'static, O, I>>,
+        loader: Arc<LoaderInner<'static, O, I>>,
         plugin: Bundle,
         depends: Vec<Bundle>,
         optional_depends: Vec<Bundle>,
+        functions: HashMap<Bundle, Registry<O>>,
     ) -> Self {
         Self {
             loader,
             plugin,
             depends,
             optional_depends,
+            functions,
         }
     }
 
-    pub fn registry(&self) -> &Registry<O> {
-        &self.loader.as_ref().registry
+    fn state(&self) -> Option<LoaderRef<'_, LoaderState<'static, O, I>>> {
+        self.loader.state.read()
+    }
+
+    fn state_mut(&self) -> Option<LoaderMut<'_, LoaderState<'static, O, I>>> {
+        self.loader.state.write()
+    }
+
+    pub fn registry(&self) -> Option<LoaderRef<'_, Registry<O>>> {
+        self.loader.registry.read()
     }
 
     pub const fn plugin(&self) -> &Bundle {
@@ -55,62 +78,87 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     where
         M: Manager<'static, O, I> + 'static,
     {
-        self.loader.as_mut().register_manager(manager)
+        self.state_mut()
+            .ok_or(RegisterManagerError::LoaderLocked)?
+            .register_manager(manager)
     }
 
     pub fn register_managers<M>(&self, managers: M) -> Result<(), RegisterManagerError>
     where
         M: IntoIterator<Item = Box<dyn Manager<'static, O, I>>>,
     {
-        self.loader.as_mut().register_managers(managers)
+        self.state_mut()
+            .ok_or(RegisterManagerError::LoaderLocked)?
+            .register_managers(managers)
     }
 
     pub fn par_register_managers<M>(&self, managers: M) -> Result<(), RegisterManagerError>
     where
         M: IntoParallelIterator<Item = Box<dyn Manager<'static, O, I>>>,
     {
-        self.loader.as_mut().par_register_managers(managers)
+        self.state_mut()
+            .ok_or(RegisterManagerError::LoaderLocked)?
+            .par_register_managers(managers)
     }
 
     pub fn unregister_manager(&self, format: &str) -> Result<(), UnregisterManagerError> {
-        self.loader.as_mut().unregister_manager(format)
+        self.state_mut()
+            .ok_or(UnregisterManagerError::LoaderLocked)?
+            .unregister_manager(format)
     }
 
-    pub fn get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'static, O, I>>> {
-        self.loader.as_ref().get_manager_ref(format)
+    pub fn get_manager_ref(
+        &self,
+        format: &str,
+    ) -> Option<LoaderRef<'_, Box<dyn Manager<'static, O, I>>>> {
+        LoaderRef::filter_map(self.state()?, |loader| loader.get_manager_ref(format))
     }
 
-    pub fn par_get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'static, O, I>>> {
-        self.loader.as_ref().par_get_manager_ref(format)
+    pub fn par_get_manager_ref(
+        &self,
+        format: &str,
+    ) -> Option<LoaderRef<'_, Box<dyn Manager<'static, O, I>>>> {
+        LoaderRef::filter_map(self.state()?, |loader| loader.par_get_manager_ref(format))
     }
 
-    pub fn get_manager_mut(&self, format: &str) -> Option<&mut Box<dyn Manager<'static, O, I>>> {
-        self.loader.as_mut().get_manager_mut(format)
+    pub fn get_manager_mut(
+        &self,
+        format: &str,
+    ) -> Option<LoaderMut<'_, Box<dyn Manager<'static, O, I>>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| loader.get_manager_mut(format))
     }
 
     pub fn par_get_manager_mut(
         &self,
         format: &str,
-    ) -> Option<&mut Box<dyn Manager<'static, O, I>>> {
-        self.loader.as_mut().par_get_manager_mut(format)
+    ) -> Option<LoaderMut<'_, Box<dyn Manager<'static, O, I>>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| {
+            loader.par_get_manager_mut(format)
+        })
     }
 
     pub fn register_plugin(&self, path: &str) -> Result<Bundle, RegisterPluginError> {
-        self.loader.as_mut().register_plugin(path)
+        self.state_mut()
+            .ok_or(RegisterPluginError::LoaderLocked)?
+            .register_plugin(path)
     }
 
     pub fn register_plugins<'b, P>(&self, paths: P) -> Result<Vec<Bundle>, RegisterPluginError>
     where
         P: IntoIterator<Item = &'b str>,
     {
-        self.loader.as_mut().register_plugins(paths)
+        self.state_mut()
+            .ok_or(RegisterPluginError::LoaderLocked)?
+            .register_plugins(paths)
     }
 
     pub fn par_register_plugins<'b, P>(&self, paths: P) -> Result<Vec<Bundle>, RegisterPluginError>
     where
         P: IntoParallelIterator<Item = &'b str>,
     {
-        self.loader.as_mut().par_register_plugins(paths)
+        self.state_mut()
+            .ok_or(RegisterPluginError::LoaderLocked)?
+            .par_register_plugins(paths)
     }
 
     pub fn unregister_plugin(
@@ -118,44 +166,60 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
         id: &str,
         version: &Version,
     ) -> Result<(), UnregisterPluginError> {
-        self.loader.as_mut().unregister_plugin(id, version)
+        self.state_mut()
+            .ok_or(UnregisterPluginError::LoaderLocked)?
+            .unregister_plugin(id, version)
     }
 
     pub fn unregister_plugin_by_bundle(
         &self,
         bundle: &Bundle,
     ) -> Result<(), UnregisterPluginError> {
-        self.loader.as_mut().unregister_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(UnregisterPluginError::LoaderLocked)?
+            .unregister_plugin_by_bundle(bundle)
     }
 
     pub fn par_unregister_plugin_by_bundle(
         &self,
         bundle: &Bundle,
     ) -> Result<(), UnregisterPluginError> {
-        self.loader.as_mut().par_unregister_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(UnregisterPluginError::LoaderLocked)?
+            .par_unregister_plugin_by_bundle(bundle)
     }
 
     pub fn load_plugin(&self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
-        self.loader.as_mut().load_plugin(id, version)
+        self.state_mut()
+            .ok_or(LoadPluginError::LoaderLocked)?
+            .load_plugin(id, version)
     }
 
     pub fn par_load_plugin(&self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
-        self.loader.as_mut().par_load_plugin(id, version)
+        self.state_mut()
+            .ok_or(LoadPluginError::LoaderLocked)?
+            .par_load_plugin(id, version)
     }
 
     pub fn load_plugin_by_bundle(&self, bundle: &Bundle) -> Result<(), LoadPluginError> {
-        self.loader.as_mut().load_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(LoadPluginError::LoaderLocked)?
+            .load_plugin_by_bundle(bundle)
     }
 
     pub fn par_load_plugin_by_bundle(&self, bundle: &Bundle) -> Result<(), LoadPluginError> {
-        self.loader.as_mut().par_load_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(LoadPluginError::LoaderLocked)?
+            .par_load_plugin_by_bundle(bundle)
     }
 
     pub fn load_plugin_now(
         &self,
         path: &str,
     ) -> Result<Bundle, (Option<RegisterPluginError>, Option<LoadPluginError>)> {
-        self.loader.as_mut().load_plugin_now(path)
+        self.state_mut()
+            .ok_or((Some(RegisterPluginError::LoaderLocked), None))?
+            .load_plugin_now(path)
     }
 
     pub fn load_plugins<'b, P>(
@@ -165,9 +229,15 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     where
         P: IntoIterator<Item = &'b str>,
     {
-        self.loader.as_mut().load_plugins(paths)
+        self.state_mut()
+            .ok_or((Some(RegisterPluginError::LoaderLocked), None))?
+            .load_plugins(paths)
     }
 
+    // Как и у `Loader`, параллельно выполняется только регистрация
+    #[deprecated(
+        note = "plugins are registered in parallel but loaded sequentially; use `load_plugins`"
+    )]
     pub fn par_load_plugins<'b, P>(
         &self,
         paths: P,
@@ -175,7 +245,9 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     where
         P: IntoParallelIterator<Item = &'b str>,
     {
-        self.loader.as_mut().par_load_plugins(paths)
+        self.state_mut()
+            .ok_or((Some(RegisterPluginError::LoaderLocked), None))?
+            .par_load_plugins(paths)
     }
 
     pub fn load_only_used_plugins<'b, P>(
@@ -192,9 +264,15 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     where
         P: IntoIterator<Item = &'b str>,
     {
-        self.loader.as_mut().load_only_used_plugins(paths)
+        self.state_mut()
+            .ok_or((Some(RegisterPluginError::LoaderLocked), None, None))?
+            .load_only_used_plugins(paths)
     }
 
+    // Как и у `Loader`, параллельно выполняется только регистрация
+    #[deprecated(
+        note = "plugins are registered in parallel but loaded sequentially; use `load_only_used_plugins`"
+    )]
     pub fn par_load_only_used_plugins<'b, P>(
         &self,
         paths: P,
@@ -209,94 +287,145 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
     where
         P: IntoParallelIterator<Item = &'b str>,
     {
-        self.loader.as_mut().par_load_only_used_plugins(paths)
+        self.state_mut()
+            .ok_or((Some(RegisterPluginError::LoaderLocked), None, None))?
+            .par_load_only_used_plugins(paths)
     }
 
     pub fn unload_plugin(&self, id: &str, version: &Version) -> Result<(), UnloadPluginError> {
-        self.loader.as_mut().unload_plugin(id, version)
+        self.state_mut()
+            .ok_or(UnloadPluginError::LoaderLocked)?
+            .unload_plugin(id, version)
     }
 
     pub fn par_unload_plugin(&self, id: &str, version: &Version) -> Result<(), UnloadPluginError> {
-        self.loader.as_mut().par_unload_plugin(id, version)
+        self.state_mut()
+            .ok_or(UnloadPluginError::LoaderLocked)?
+            .par_unload_plugin(id, version)
     }
 
     pub fn unload_plugin_by_bundle(&self, bundle: &Bundle) -> Result<(), UnloadPluginError> {
-        self.loader.as_mut().unload_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(UnloadPluginError::LoaderLocked)?
+            .unload_plugin_by_bundle(bundle)
     }
 
     pub fn par_unload_plugin_by_bundle(&self, bundle: &Bundle) -> Result<(), UnloadPluginError> {
-        self.loader.as_mut().par_unload_plugin_by_bundle(bundle)
+        self.state_mut()
+            .ok_or(UnloadPluginError::LoaderLocked)?
+            .par_unload_plugin_by_bundle(bundle)
     }
 
-    pub fn get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'static, O, I>> {
-        self.loader.as_ref().get_plugin(id, version)
+    pub fn get_plugin(
+        &self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderRef<'_, Plugin<'static, O, I>>> {
+        LoaderRef::filter_map(self.state()?, |loader| loader.get_plugin(id, version))
     }
 
-    pub fn par_get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'static, O, I>> {
-        self.loader.as_ref().par_get_plugin(id, version)
+    pub fn par_get_plugin(
+        &self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderRef<'_, Plugin<'static, O, I>>> {
+        LoaderRef::filter_map(self.state()?, |loader| loader.par_get_plugin(id, version))
     }
 
-    pub fn get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'static, O, I>> {
-        self.loader.as_ref().get_plugin_by_bundle(bundle)
+    pub fn get_plugin_by_bundle(
+        &self,
+        bundle: &Bundle,
+    ) -> Option<LoaderRef<'_, Plugin<'static, O, I>>> {
+        LoaderRef::filter_map(self.state()?, |loader| loader.get_plugin_by_bundle(bundle))
     }
 
-    pub fn par_get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'static, O, I>> {
-        self.loader.as_ref().par_get_plugin_by_bundle(bundle)
+    pub fn par_get_plugin_by_bundle(
+        &self,
+        bundle: &Bundle,
+    ) -> Option<LoaderRef<'_, Plugin<'static, O, I>>> {
+        LoaderRef::filter_map(self.state()?, |loader| {
+            loader.par_get_plugin_by_bundle(bundle)
+        })
     }
 
     pub fn get_plugin_mut(
         &self,
         id: &str,
         version: &Version,
-    ) -> Option<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().get_plugin_mut(id, version)
+    ) -> Option<LoaderMut<'_, Plugin<'static, O, I>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| {
+            loader.get_plugin_mut(id, version)
+        })
     }
 
     pub fn par_get_plugin_mut(
         &self,
         id: &str,
         version: &Version,
-    ) -> Option<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().par_get_plugin_mut(id, version)
+    ) -> Option<LoaderMut<'_, Plugin<'static, O, I>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| {
+            loader.par_get_plugin_mut(id, version)
+        })
     }
 
-    pub fn get_plugin_mut_by_bundle(&self, bundle: &Bundle) -> Option<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().get_plugin_mut_by_bundle(bundle)
+    pub fn get_plugin_mut_by_bundle(
+        &self,
+        bundle: &Bundle,
+    ) -> Option<LoaderMut<'_, Plugin<'static, O, I>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| {
+            loader.get_plugin_mut_by_bundle(bundle)
+        })
     }
 
     pub fn par_get_plugin_mut_by_bundle(
         &self,
         bundle: &Bundle,
-    ) -> Option<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().par_get_plugin_mut_by_bundle(bundle)
+    ) -> Option<LoaderMut<'_, Plugin<'static, O, I>>> {
+        LoaderMut::filter_map(self.state_mut()?, |loader| {
+            loader.par_get_plugin_mut_by_bundle(bundle)
+        })
+    }
+
+    pub fn get_plugins_by_id(&self, id: &str) -> Option<Vec<LoaderRef<'_, Plugin<'static, O, I>>>> {
+        self.loader.get_plugins_by_id(id)
+    }
+
+    pub fn par_get_plugins_by_id(
+        &self,
+        id: &str,
+    ) -> Option<Vec<LoaderRef<'_, Plugin<'static, O, I>>>> {
+        self.loader.par_get_plugins_by_id(id)
     }
 
-    pub fn get_plugins_by_id(&self, id: &str) -> Vec<&Plugin<'static, O, I>> {
-        self.loader.as_ref().get_plugins_by_id(id)
+    pub fn get_plugins_by_id_mut(&self, id: &str) -> Option<PluginsMut<'_, 'static, O, I>> {
+        self.loader.get_plugins_by_id_mut(id)
     }
 
-    pub fn par_get_plugins_by_id(&self, id: &str) -> Vec<&Plugin<'static, O, I>> {
-        self.loader.as_ref().par_get_plugins_by_id(id)
+    pub fn par_get_plugins_by_id_mut(&self, id: &str) -> Option<PluginsMut<'_, 'static, O, I>> {
+        self.loader.par_get_plugins_by_id_mut(id)
     }
 
-    pub fn get_plugins_by_id_mut(&self, id: &str) -> Vec<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().get_plugins_by_id_mut(id)
+    pub fn subscribe<F>(&self, handler: F) -> Option<Subscription>
+    where
+        F: Fn(&LoaderEvent) + Send + Sync + 'static,
+    {
+        Some(self.state_mut()?.events.subscribe(handler))
     }
 
-    pub fn par_get_plugins_by_id_mut(&self, id: &str) -> Vec<&mut Plugin<'static, O, I>> {
-        self.loader.as_mut().par_get_plugins_by_id_mut(id)
+    pub fn unsubscribe(&self, subscription: Subscription) -> Option<bool> {
+        Some(self.state_mut()?.events.unsubscribe(subscription))
     }
 
-    pub fn get_plugins(&self) -> &Vec<Plugin<'static, O, I>> {
-        self.loader.as_ref().get_plugins()
+    pub fn get_plugins(&self) -> Option<LoaderRef<'_, Vec<Plugin<'static, O, I>>>> {
+        Some(LoaderRef::map(self.state()?, |loader| &loader.plugins))
     }
 
-    pub fn get_registry(&self) -> &Registry<O> {
-        self.loader.as_ref().get_registry()
+    pub fn get_registry(&self) -> Option<LoaderRef<'_, Registry<O>>> {
+        self.loader.registry.read()
     }
 
-    pub fn get_requests(&self) -> &Requests {
-        self.loader.as_ref().get_requests()
+    pub fn get_requests(&self) -> Option<LoaderRef<'_, Requests>> {
+        self.loader.requests.read()
     }
 
     pub fn call_request(
@@ -304,7 +433,9 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
         name: &str,
         args: &[Variable],
     ) -> Result<Vec<O>, PluginCallRequestError> {
-        self.loader.as_ref().call_request(name, args)
+        self.state()
+            .ok_or(PluginCallRequestError::LoaderLocked)?
+            .call_request(name, args)
     }
 
     pub fn par_call_request(
@@ -312,7 +443,9 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
         name: &str,
         args: &[Variable],
     ) -> Result<Vec<O>, PluginCallRequestError> {
-        self.loader.as_ref().par_call_request(name, args)
+        self.state()
+            .ok_or(PluginCallRequestError::LoaderLocked)?
+            .par_call_request(name, args)
     }
 
     // Дополнительные функции
@@ -325,19 +458,14 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
         name: &str,
         args: &[Variable],
     ) -> Result<O, CallFunctionDependError> {
-        let depend = self
+        let registry = self
             .depends
             .iter()
             .find(|&depend| *depend == (id, version))
+            .and_then(|depend| self.functions.get(depend))
             .ok_or(CallFunctionDependError::DependNotFound)?;
 
-        let plugin = self
-            .loader
-            .as_ref()
-            .get_plugin_by_bundle(depend)
-            .ok_or(CallFunctionDependError::DependNotFound)?;
-
-        Ok(plugin.call_function(name, args)?)
+        Ok(call_function(registry, name, args)?)
     }
 
     //TODO: Добавить параллельную версию
@@ -348,14 +476,25 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Api<O, I> {
         name: &str,
         args: &[Variable],
     ) -> Result<Option<O>, PluginCallFunctionError> {
-        let depend = self
-            .optional_depends
+        self.optional_depends
             .iter()
-            .find(|&depend| *depend == (id, version));
-
-        depend
-            .and_then(|depend| self.loader.as_ref().get_plugin_by_bundle(depend))
-            .map(|plugin| plugin.call_function(name, args))
+            .find(|&depend| *depend == (id, version))
+            .and_then(|depend| self.functions.get(depend))
+            .map(|registry| call_function(registry, name, args))
             .transpose()
     }
 }
+
+fn call_function<O: Send + Sync>(
+    registry: &Registry<O>,
+    name: &str,
+    args: &[Variable],
+) -> Result<O, PluginCallFunctionError> {
+    registry
+        .iter()
+        .find_map(|function| match function.name() == name {
+            true => Some(function.call(args)),
+            false => None,
+        })
+        .ok_or(PluginCallFunctionError::NotFound)
+}
@@ -13,41 +13,56 @@ pub struct Bundle {
 }
 
 impl Bundle {
+    // Разбирает имя вида `<id>-v<version>.<format>`
     pub fn from_filename<S>(filename: &S) -> Result<Self, BundleFromError>
     where
         S: AsRef<OsStr> + ?Sized,
     {
-        let mut path = filename
+        let name = filename
             .as_ref()
             .to_str()
-            .ok_or(BundleFromError::OsStrToStrFailed)?
-            .to_string();
-
-        let format = path
-            .drain(path.rfind('.').ok_or(BundleFromError::FormatFailed)? + 1..)
-            .collect::<String>();
-        let version = path
-            .drain(path.rfind("-v").ok_or(BundleFromError::VersionFailed)? + 2..path.len() - 1)
-            .collect::<String>();
-        let id = path
-            .drain(..path.rfind("-v").ok_or(BundleFromError::IDFailed)?)
-            .collect::<String>();
-
-        if format.is_empty() {
-            return Err(BundleFromError::FormatFailed);
-        }
-        if version.is_empty() {
-            return Err(BundleFromError::VersionFailed);
-        }
-        if id.is_empty() {
-            return Err(BundleFromError::IDFailed);
-        }
+            .ok_or(BundleFromError::OsStrToStrFailed)?;
 
-        Ok(Self {
-            id,
-            version: Version::parse(version.as_str())?,
-            format,
-        })
+        let Some((stem, format)) = name.rsplit_once('.') else {
+            return Err(BundleFromError::FormatFailed {
+                name: name.to_string(),
+                reason: "missing `.<format>` extension",
+            });
+        };
+        let Some((id, version)) = stem.rsplit_once("-v") else {
+            return Err(BundleFromError::VersionFailed {
+                name: name.to_string(),
+                reason: "missing `-v<version>` before the format",
+            });
+        };
+
+        let bundle = Self {
+            id: id.to_string(),
+            version: Version::parse(version).map_err(|source| BundleFromError::ParseVersion {
+                name: name.to_string(),
+                source,
+            })?,
+            format: format.to_string(),
+        };
+        bundle.check(name)?;
+
+        Ok(bundle)
+    }
+
+    // Пустые части имени недопустимы при любом способе именования
+    pub(crate) fn check(&self, name: &str) -> Result<(), BundleFromError> {
+        let name = name.to_string();
+        match (self.id.is_empty(), self.format.is_empty()) {
+            (true, _) => Err(BundleFromError::IDFailed {
+                name,
+                reason: "the ID is empty",
+            }),
+            (_, true) => Err(BundleFromError::FormatFailed {
+                name,
+                reason: "the format is empty",
+            }),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -92,3 +107,30 @@ impl Display for Bundle {
         write!(f, "{}-v{}.{}", self.id, self.version, self.format)
     }
 }
+
+#[test]
+fn parse_filename() {
+    let bundle = Bundle::from_filename("nav-vision-v1.2.0.vpl").unwrap();
+    assert_eq!(bundle.id, "nav-vision");
+    assert_eq!(bundle.version, Version::new(1, 2, 0));
+    assert_eq!(bundle.format, "vpl");
+
+    let errors = [
+        "nav-vision",
+        "navigation.vpl",
+        "nav-vision.vpl",
+        "-v1.0.0.vpl",
+        "nav-v1.0.0.",
+    ]
+    .map(|name| Bundle::from_filename(name).unwrap_err().to_string());
+    assert_eq!(
+        errors,
+        [
+            "Failed to get format from `nav-vision`: missing `.<format>` extension",
+            "Failed to get version from `navigation.vpl`: missing `-v<version>` before the format",
+            "Failed to parse version of `nav-vision.vpl`",
+            "Failed to get ID from `-v1.0.0.vpl`: the ID is empty",
+            "Failed to get format from `nav-v1.0.0.`: the format is empty",
+        ]
+    );
+}
@@ -1,4 +1,8 @@
-use crate::{function::Function, utils::RegisterRequestError, Info, Plugin, Requests};
+use crate::{
+    function::Function,
+    utils::{PluginRegisterFunctionError, RegisterRequestError},
+    Info, Plugin, Requests,
+};
 
 pub struct LoadPluginContext<'a, 'b, O: Send + Sync, I: Info> {
     plugin: &'b mut Plugin<'a, O, I>,
@@ -18,18 +22,29 @@ impl<'a, 'b, O: Send + Sync, I: Info> LoadPluginContext<'a, 'b, O, I> {
         self.requests
     }
 
+    pub fn register_function<F>(&mut self, function: F) -> Result<(), PluginRegisterFunctionError>
+    where
+        F: Function<Output = O> + 'static,
+    {
+        self.plugin.register_function(function)
+    }
+
     //TODO: Добавить параллельную версию
     pub fn register_request<F>(&mut self, request: F) -> Result<(), RegisterRequestError>
     where
         F: Function<Output = O> + 'static,
     {
         if let Some(req) = self.requests.iter().find(|req| *req.name == request.name()) {
-            for input in req.inputs.iter() {
-                request
-                    .inputs()
+            // Типы сравниваются попарно, списки - вместе с типом элементов
+            let inputs = request.inputs();
+            if req.inputs.len() != inputs.len()
+                || req
+                    .inputs
                     .iter()
-                    .find(|arg| *input == arg.ty)
-                    .ok_or(RegisterRequestError::ArgumentsIncorrectly)?;
+                    .zip(inputs.iter())
+                    .any(|(ty, arg)| *ty != arg.ty)
+            {
+                return Err(RegisterRequestError::ArgumentsIncorrectly);
             }
 
             if req.output != request.output().map(|arg| arg.ty) {
@@ -31,7 +31,7 @@ impl<'a, 'b, O: Send + Sync, I: Info> LoaderContext<'a, 'b, O, I> {
     }
 
     pub fn register_request(&mut self, request: Request) {
-        self.loader.requests.push(request);
+        self.loader.inner().requests.write_blocking().push(request);
     }
 
     //TODO: Добавить параллельную версию метода
@@ -39,14 +39,14 @@ impl<'a, 'b, O: Send + Sync, I: Info> LoaderContext<'a, 'b, O, I> {
     where
 	IT: IntoIterator<Item = Request>,
     {
-        self.loader.requests.extend(requests);
+        self.loader.inner().requests.write_blocking().extend(requests);
     }
 
     pub fn register_function<F>(&mut self, function: F)
     where
         F: Function<Output = O> + 'static,
     {
-        self.loader.registry.push(Arc::new(function));
+        self.loader.inner().registry.write_blocking().push(Arc::new(function));
     }
 
     //TODO: Добавить параллельную версию метода
@@ -55,7 +55,7 @@ impl<'a, 'b, O: Send + Sync, I: Info> LoaderContext<'a, 'b, O, I> {
         F: Function<Output = O> + 'static,
         IT: IntoIterator<Item = F>,
     {
-        self.loader.registry.extend(
+        self.loader.inner().registry.write_blocking().extend(
             functions
                 .into_iter()
                 .map(|f| Arc::new(f) as Arc<dyn Function<Output = O>>),
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use semver::Version;
+
+use crate::{
+    utils::{BundleFromError, DiscoverPluginsError, RegisterPluginError},
+    Bundle, Info, Loader,
+};
+
+// Параметры обхода каталогов с плагинами
+#[derive(Debug, Clone)]
+pub struct DiscoverOptions {
+    pub max_depth: usize,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub registered_formats_only: bool,
+}
+
+impl Default for DiscoverOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 1,
+            include: vec![],
+            exclude: vec![],
+            registered_formats_only: false,
+        }
+    }
+}
+
+impl DiscoverOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_include<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    pub fn with_exclude<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    pub fn with_registered_formats_only(mut self) -> Self {
+        self.registered_formats_only = true;
+        self
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SkipReason {
+    #[error("Invalid bundle name")]
+    BadName(#[source] BundleFromError),
+    #[error("Unknown plugin manager for the format '{0}'")]
+    UnknownFormat(String),
+    #[error("A plugin with ID `{id}` and version `{version}` already exists")]
+    Duplicate { id: String, version: Version },
+    #[error("Failed to register plugin")]
+    RegisterFailed(#[source] RegisterPluginError),
+}
+
+impl From<RegisterPluginError> for SkipReason {
+    fn from(e: RegisterPluginError) -> Self {
+        match e {
+            RegisterPluginError::BundleFromFailed(e) => Self::BadName(e),
+            RegisterPluginError::UnknownManagerFormat(format) => Self::UnknownFormat(format),
+            RegisterPluginError::AlreadyExistsIDAndVersion(id, version) => {
+                Self::Duplicate { id, version }
+            }
+            e => Self::RegisterFailed(e),
+        }
+    }
+}
+
+// Что стало плагинами, а что было пропущено и почему
+#[derive(Debug, Default)]
+pub struct DiscoverReport {
+    pub plugins: Vec<(PathBuf, Bundle)>,
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
+impl DiscoverReport {
+    pub fn bundles(&self) -> Vec<Bundle> {
+        self.plugins
+            .iter()
+            .map(|(_, bundle)| bundle.clone())
+            .collect()
+    }
+}
+
+struct Discovery {
+    options: DiscoverOptions,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    report: DiscoverReport,
+}
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
+    // Регистрирует все бандлы из корней, не прерываясь на ошибках отдельных плагинов
+    pub fn discover<R>(
+        &mut self,
+        roots: R,
+        options: DiscoverOptions,
+    ) -> Result<DiscoverReport, DiscoverPluginsError>
+    where
+        R: IntoIterator,
+        R::Item: AsRef<Path>,
+    {
+        let mut discovery = Discovery {
+            include: patterns(&options.include)?,
+            exclude: patterns(&options.exclude)?,
+            options,
+            report: DiscoverReport::default(),
+        };
+
+        for root in roots {
+            let root = root.as_ref();
+            discovery.walk(self, root, root, 1)?;
+        }
+
+        Ok(discovery.report)
+    }
+}
+
+impl Discovery {
+    fn walk<O: Send + Sync, I: Info>(
+        &mut self,
+        loader: &mut Loader<'_, O, I>,
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+    ) -> Result<(), DiscoverPluginsError> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .and_then(|entries| entries.map(|entry| Ok(entry?.path())).collect())
+            .map_err(|e| DiscoverPluginsError::ReadDirFailed(dir.to_path_buf(), e))?;
+        paths.sort();
+
+        for path in paths {
+            let name = path.strip_prefix(root).unwrap();
+            if matches(&self.exclude, name) {
+                continue;
+            }
+
+            // Каталог, не являющийся бандлом, обходится, пока позволяет глубина
+            let bundle = match path.is_dir() {
+                true => Some(loader.state().naming.bundle(&path)),
+                false => None,
+            };
+            if matches!(bundle, Some(Err(_))) && depth < self.options.max_depth {
+                self.walk(loader, root, &path, depth + 1)?;
+                continue;
+            }
+
+            #[cfg(not(feature = "archive"))]
+            if bundle.is_none() {
+                continue;
+            }
+
+            if !self.include.is_empty() && !matches(&self.include, name) {
+                continue;
+            }
+
+            if self.options.registered_formats_only {
+                let format = match &bundle {
+                    Some(Ok(bundle)) => Some(bundle.format.clone()),
+                    _ => path
+                        .extension()
+                        .map(|format| format.to_string_lossy().to_string()),
+                };
+                // Без расширения формат неизвестен, имя отвергнет регистрация
+                if let Some(format) = format {
+                    if loader.get_manager_ref(&format).is_none() {
+                        let reason = SkipReason::UnknownFormat(format);
+                        self.report.skipped.push((path, reason));
+                        continue;
+                    }
+                }
+            }
+
+            let result = path
+                .to_str()
+                .ok_or(RegisterPluginError::BundleFromFailed(
+                    BundleFromError::OsStrToStrFailed,
+                ))
+                .and_then(|path| loader.register_plugin(path));
+            match result {
+                Ok(bundle) => self.report.plugins.push((path, bundle)),
+                Err(e) => self.report.skipped.push((path, e.into())),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn patterns(patterns: &[String]) -> Result<Vec<Pattern>, DiscoverPluginsError> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern)
+                .map_err(|e| DiscoverPluginsError::InvalidPattern(pattern.clone(), e))
+        })
+        .collect()
+}
+
+fn matches(patterns: &[Pattern], path: &Path) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| pattern.matches_path_with(path, MATCH_OPTIONS))
+}
@@ -0,0 +1,83 @@
+use crate::{
+    utils::{IntegrityError, LoadPluginError},
+    Bundle,
+};
+
+#[derive(Debug)]
+pub enum LoaderEvent<'e> {
+    ManagerRegistered {
+        format: &'e str,
+    },
+    ManagerUnregistered {
+        format: &'e str,
+    },
+    IntegrityWarning {
+        bundle: &'e Bundle,
+        error: &'e IntegrityError,
+    },
+    PluginRegistered {
+        bundle: &'e Bundle,
+    },
+    PluginLoading {
+        bundle: &'e Bundle,
+    },
+    PluginLoaded {
+        bundle: &'e Bundle,
+    },
+    PluginLoadFailed {
+        bundle: &'e Bundle,
+        error: &'e LoadPluginError,
+    },
+    PluginUnloaded {
+        bundle: &'e Bundle,
+    },
+    PluginUnregistered {
+        bundle: &'e Bundle,
+    },
+    RequestRegistered {
+        bundle: &'e Bundle,
+        name: &'e str,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(usize);
+
+type Handler = Box<dyn Fn(&LoaderEvent) + Send + Sync>;
+
+pub(crate) struct Events {
+    handlers: Vec<(Subscription, Handler)>,
+    next: usize,
+}
+
+impl Events {
+    pub(crate) const fn new() -> Self {
+        Self {
+            handlers: vec![],
+            next: 0,
+        }
+    }
+
+    pub(crate) fn subscribe<F>(&mut self, handler: F) -> Subscription
+    where
+        F: Fn(&LoaderEvent) + Send + Sync + 'static,
+    {
+        let subscription = Subscription(self.next);
+        self.next += 1;
+
+        self.handlers.push((subscription, Box::new(handler)));
+        subscription
+    }
+
+    pub(crate) fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let len = self.handlers.len();
+        self.handlers.retain(|(s, _)| *s != subscription);
+        len != self.handlers.len()
+    }
+
+    pub(crate) fn emit(&self, event: LoaderEvent) {
+        self.handlers
+            .iter()
+            .for_each(|(_, handler)| handler(&event));
+    }
+}
@@ -8,6 +8,8 @@ use crate::variable::VariableType;
 pub struct Arg {
     pub name: String,
     pub ty: VariableType,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl Arg {
@@ -15,8 +17,14 @@ impl Arg {
         Self {
             name: name.into(),
             ty,
+            description: None,
         }
     }
+
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
 }
 
 impl Default for Arg {
@@ -24,6 +32,7 @@ impl Default for Arg {
         Self {
             name: "arg".to_string(),
             ty: Default::default(),
+            description: None,
         }
     }
 }
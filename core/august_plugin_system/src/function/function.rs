@@ -1,6 +1,12 @@
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
 
-use crate::variable::Variable;
+use crate::{
+    utils::CallArgumentsError,
+    variable::{CoercionPolicy, Variable},
+};
 
 use super::Arg;
 
@@ -8,9 +14,94 @@ pub trait Function: Send + Sync {
     type Output: Send + Sync;
 
     fn name(&self) -> String;
+    fn description(&self) -> Option<String> {
+        None
+    }
     fn inputs(&self) -> Vec<Arg>;
     fn output(&self) -> Option<Arg>;
     fn call(&self, args: &[Variable]) -> Self::Output;
+
+    // Вызов с проверкой количества и типов аргументов
+    fn call_checked(&self, args: &[Variable]) -> Result<Self::Output, CallArgumentsError> {
+        check_arguments(&self.inputs(), args)?;
+        Ok(self.call(args))
+    }
+}
+
+pub fn check_arguments(inputs: &[Arg], args: &[Variable]) -> Result<(), CallArgumentsError> {
+    // Необязательные аргументы в конце можно не передавать
+    let required = inputs.len()
+        - inputs
+            .iter()
+            .rev()
+            .take_while(|arg| arg.ty.is_optional())
+            .count();
+    if args.len() < required || args.len() > inputs.len() {
+        return Err(CallArgumentsError::Count {
+            expected: match args.len() < required {
+                true => required,
+                false => inputs.len(),
+            },
+            actual: args.len(),
+        });
+    }
+
+    for (index, (arg, var)) in inputs.iter().zip(args.iter()).enumerate() {
+        if !arg.ty.matches(var) {
+            return Err(CallArgumentsError::Type {
+                index,
+                name: arg.name.clone(),
+                expected: arg.ty.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Неприводимые аргументы остаются как есть и не проходят проверку типов
+pub fn coerce_arguments(
+    inputs: &[Arg],
+    args: &[Variable],
+    policy: CoercionPolicy,
+) -> Vec<Variable> {
+    args.iter()
+        .enumerate()
+        .map(|(index, var)| match inputs.get(index) {
+            Some(arg) => var
+                .coerce_to_with(&arg.ty, policy)
+                .unwrap_or_else(|_| var.clone()),
+            None => var.clone(),
+        })
+        .collect()
+}
+
+impl<F: Function + ?Sized> Function for Arc<F> {
+    type Output = F::Output;
+
+    fn name(&self) -> String {
+        self.as_ref().name()
+    }
+
+    fn description(&self) -> Option<String> {
+        self.as_ref().description()
+    }
+
+    fn inputs(&self) -> Vec<Arg> {
+        self.as_ref().inputs()
+    }
+
+    fn output(&self) -> Option<Arg> {
+        self.as_ref().output()
+    }
+
+    fn call(&self, args: &[Variable]) -> Self::Output {
+        self.as_ref().call(args)
+    }
+
+    fn call_checked(&self, args: &[Variable]) -> Result<Self::Output, CallArgumentsError> {
+        self.as_ref().call_checked(args)
+    }
 }
 
 impl<O: Send + Sync> PartialEq for dyn Function<Output = O> {
@@ -25,9 +116,12 @@ impl<O: Send + Sync> Eq for dyn Function<Output = O> {}
 
 impl<O: Send + Sync> Display for dyn Function<Output = O> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        //TODO: Внедрить описание функций в August
-        // // Комментарий в виде описания функции
-        // write!(f, "# {}\n", self.description);
+        // Комментарий в виде описания функции
+        if let Some(description) = self.description() {
+            for line in description.lines() {
+                writeln!(f, "# {line}")?;
+            }
+        }
 
         // Функция
         write!(
@@ -57,8 +151,10 @@ pub type FunctionOutput = Result<Option<Variable>, Box<dyn std::error::Error + S
 
 pub struct DynamicFunction {
     name: String,
+    description: Option<String>,
     inputs: Vec<Arg>,
     output: Option<Arg>,
+    coercion: Option<CoercionPolicy>,
     ptr: Box<dyn Fn(&[Variable]) -> FunctionOutput + Send + Sync>,
 }
 
@@ -70,11 +166,51 @@ impl DynamicFunction {
     {
         Self {
             name: name.into(),
+            description: None,
             inputs,
             output,
+            coercion: None,
             ptr: Box::new(ptr),
         }
     }
+
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    // Перед вызовом аргументы приводятся к типам входных параметров
+    pub fn with_coercion(mut self, policy: CoercionPolicy) -> Self {
+        self.coercion = Some(policy);
+        self
+    }
+
+    fn call_with(&self, args: &[Variable], coercion: Option<CoercionPolicy>) -> FunctionOutput {
+        let mut owned = None;
+
+        // Недостающие необязательные аргументы в конце заменяются на `Null`
+        let missing = self.inputs.get(args.len()..).unwrap_or_default();
+        if !missing.is_empty() && missing.iter().all(|arg| arg.ty.is_optional()) {
+            let mut args = args.to_vec();
+            args.resize(self.inputs.len(), Variable::Null);
+            owned = Some(args);
+        }
+
+        if let Some(policy) = coercion {
+            let args = owned.as_deref().unwrap_or(args);
+            owned = Some(
+                args.iter()
+                    .enumerate()
+                    .map(|(index, var)| match self.inputs.get(index) {
+                        Some(arg) => var.coerce_to_with(&arg.ty, policy),
+                        None => Ok(var.clone()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+
+        (self.ptr)(owned.as_deref().unwrap_or(args))
+    }
 }
 
 impl Function for DynamicFunction {
@@ -84,6 +220,10 @@ impl Function for DynamicFunction {
         self.name.clone()
     }
 
+    fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
     fn inputs(&self) -> Vec<Arg> {
         self.inputs.clone()
     }
@@ -93,7 +233,18 @@ impl Function for DynamicFunction {
     }
 
     fn call(&self, args: &[Variable]) -> Self::Output {
-        (self.ptr)(args)
+        self.call_with(args, self.coercion)
+    }
+
+    // Аргументы приводятся до проверки, чтобы не отклонять приводимые значения
+    fn call_checked(&self, args: &[Variable]) -> Result<Self::Output, CallArgumentsError> {
+        let coerced = self
+            .coercion
+            .map(|policy| coerce_arguments(&self.inputs, args, policy));
+        let args = coerced.as_deref().unwrap_or(args);
+
+        check_arguments(&self.inputs, args)?;
+        Ok(self.call_with(args, None))
     }
 }
 
@@ -128,6 +279,182 @@ fn function_call() {
     assert_eq!(c.unwrap(), Some(3.into()));
 }
 
+#[test]
+fn optional_call() {
+    use crate::variable::VariableType;
+
+    let func = DynamicFunction::new(
+        "greet",
+        vec![
+            Arg::new("name", VariableType::String),
+            Arg::new("title", VariableType::optional(VariableType::String)),
+        ],
+        Some(Arg::new("greeting", VariableType::String)),
+        |args| -> FunctionOutput {
+            let name = args[0].parse_ref::<String>();
+            let greeting = match args[1].parse_ref::<Option<String>>() {
+                Some(title) => format!("Hello, {title} {name}"),
+                None => format!("Hello, {name}"),
+            };
+
+            Ok(Some(greeting.into()))
+        },
+    );
+
+    let result = func.call(&["Alice".into()]);
+    assert_eq!(result.unwrap(), Some("Hello, Alice".into()));
+
+    let result = func.call(&["Alice".into(), "Dr.".into()]);
+    assert_eq!(result.unwrap(), Some("Hello, Dr. Alice".into()));
+}
+
+#[test]
+fn checked_call() {
+    use crate::variable::VariableType;
+
+    let func = DynamicFunction::new(
+        "scale",
+        vec![
+            Arg::new("values", VariableType::list(VariableType::I32)),
+            Arg::new("factor", VariableType::optional(VariableType::I32)),
+        ],
+        Some(Arg::new("output", VariableType::list(VariableType::I32))),
+        |args| -> FunctionOutput {
+            let factor = args[1].parse_ref::<Option<i32>>().unwrap_or(&2);
+            let values = args[0].parse_ref::<Vec<i32>>();
+
+            Ok(Some(
+                values
+                    .into_iter()
+                    .map(|v| v * factor)
+                    .collect::<Vec<_>>()
+                    .into(),
+            ))
+        },
+    );
+
+    let result = func.call_checked(&[vec![1, 2].into()]).unwrap();
+    assert_eq!(result.unwrap(), Some(vec![2, 4].into()));
+
+    let result = func.call_checked(&[vec![1, 2].into(), 3.into()]).unwrap();
+    assert_eq!(result.unwrap(), Some(vec![3, 6].into()));
+
+    match func.call_checked(&[]) {
+        Err(CallArgumentsError::Count { expected, actual }) => {
+            assert_eq!((expected, actual), (1, 0))
+        }
+        _ => panic!("the number of arguments is not checked"),
+    }
+
+    match func.call_checked(&[vec![1, 2].into(), 3.into(), 4.into()]) {
+        Err(CallArgumentsError::Count { expected, actual }) => {
+            assert_eq!((expected, actual), (2, 3))
+        }
+        _ => panic!("the number of arguments is not checked"),
+    }
+
+    match func.call_checked(&[vec!["1"].into()]) {
+        Err(e @ CallArgumentsError::Type { .. }) => assert_eq!(
+            e.to_string(),
+            "Argument `values` (#0) must be of type `List<I32>`"
+        ),
+        _ => panic!("the argument types are not checked"),
+    }
+}
+
+#[test]
+fn coerced_call() {
+    use crate::variable::VariableType;
+
+    let func = DynamicFunction::new(
+        "add",
+        vec![
+            Arg::new("a", VariableType::I32),
+            Arg::new("b", VariableType::I32),
+        ],
+        Some(Arg::new("c", VariableType::I32)),
+        |args| -> FunctionOutput {
+            let a = args[0].try_parse_ref::<i32>()?;
+            let b = args[1].try_parse_ref::<i32>()?;
+
+            Ok(Some((a + b).into()))
+        },
+    );
+
+    // Без приведения числа из скриптов не подходят под `i32`
+    assert!(func.call(&[1_i64.into(), 2.0_f64.into()]).is_err());
+
+    let func = func.with_coercion(CoercionPolicy::Checked);
+    let result = func.call(&[1_i64.into(), 2.0_f64.into()]);
+    assert_eq!(result.unwrap(), Some(3.into()));
+
+    assert!(func.call(&[1_i64.into(), 2.5_f64.into()]).is_err());
+}
+
+#[test]
+fn coerced_checked_call() {
+    use crate::variable::VariableType;
+
+    let func = DynamicFunction::new(
+        "add",
+        vec![
+            Arg::new("a", VariableType::I32),
+            Arg::new("b", VariableType::I32),
+        ],
+        Some(Arg::new("c", VariableType::I32)),
+        |args| -> FunctionOutput {
+            let a = args[0].try_parse_ref::<i32>()?;
+            let b = args[1].try_parse_ref::<i32>()?;
+
+            Ok(Some((a + b).into()))
+        },
+    );
+
+    match func.call_checked(&[1_i64.into(), 2.into()]) {
+        Err(CallArgumentsError::Type { index, .. }) => assert_eq!(index, 0),
+        _ => panic!("the argument types are not checked"),
+    }
+
+    // Проверка типов выполняется после приведения
+    let func = func.with_coercion(CoercionPolicy::Checked);
+    let result = func.call_checked(&[1_i64.into(), 2.into()]).unwrap();
+    assert_eq!(result.unwrap(), Some(3.into()));
+
+    let func: Arc<dyn Function<Output = FunctionOutput>> = Arc::new(func);
+    let result = func.call_checked(&[1_i64.into(), 2.0_f64.into()]).unwrap();
+    assert_eq!(result.unwrap(), Some(3.into()));
+
+    match func.call_checked(&[1_i64.into(), 2.5_f64.into()]) {
+        Err(CallArgumentsError::Type { index, .. }) => assert_eq!(index, 1),
+        _ => panic!("the argument types are not checked"),
+    }
+}
+
+#[test]
+fn described_function() {
+    use crate::variable::VariableType;
+
+    let func: Box<dyn Function<Output = FunctionOutput>> = Box::new(
+        DynamicFunction::new(
+            "add",
+            vec![
+                Arg::new("a", VariableType::I32).with_description("First term"),
+                Arg::new("b", VariableType::I32),
+            ],
+            Some(Arg::new("c", VariableType::I32)),
+            |_| -> FunctionOutput { Ok(None) },
+        )
+        .with_description("Adds two numbers"),
+    );
+
+    assert_eq!(func.description(), Some("Adds two numbers".to_string()));
+    assert_eq!(func.inputs()[0].description, Some("First term".to_string()));
+    assert_eq!(
+        func.to_string(),
+        "# Adds two numbers\nadd(a: I32, b: I32) -> c(I32)"
+    );
+}
+
 #[test]
 fn parallel_call() {
     use crate::variable::VariableType;
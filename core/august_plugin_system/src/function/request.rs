@@ -9,6 +9,8 @@ pub struct Request {
     pub name: String,
     pub inputs: Vec<VariableType>,
     pub output: Option<VariableType>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl Request {
@@ -21,12 +23,25 @@ impl Request {
             name: name.into(),
             inputs,
             output,
+            description: None,
         }
     }
+
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
 }
 
 impl Display for Request {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Комментарий в виде описания запроса
+        if let Some(description) = &self.description {
+            for line in description.lines() {
+                writeln!(f, "# {line}")?;
+            }
+        }
+
         write!(
             f,
             "{}({}) -> {}",
@@ -36,7 +51,7 @@ impl Display for Request {
                 .map(|x| format!("{x}"))
                 .collect::<Vec<_>>()
                 .join(", "),
-            match self.output {
+            match &self.output {
                 Some(x) => format!("{x}"),
                 None => "void".to_string(),
             }
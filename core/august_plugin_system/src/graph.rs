@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{resolver, Bundle, Depend, Info, Loader, Plugin};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyNode {
+    pub bundle: Bundle,
+    pub is_load: bool,
+    // Причина, по которой зависимости плагина не удаётся разрешить
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyEdge {
+    pub from: Bundle,
+    pub depend: Depend,
+    pub optional: bool,
+    pub resolved: Option<Bundle>,
+}
+
+impl DependencyGraph {
+    pub(crate) fn new<O: Send + Sync, I: Info>(plugins: &[Plugin<'_, O, I>]) -> Self {
+        let mut nodes = vec![];
+        let mut edges = vec![];
+
+        for (index, plugin) in plugins.iter().enumerate() {
+            let resolution = resolver::resolve(plugins, &[index]);
+
+            let info = &plugin.info.info;
+            let depends = info.depends().iter().map(|depend| (depend, false));
+            let optional_depends = info.optional_depends().iter().map(|depend| (depend, true));
+
+            for (depend, optional) in depends.chain(optional_depends) {
+                let resolved = match &resolution {
+                    Ok(resolution) => resolution
+                        .depends(plugins, index)
+                        .into_iter()
+                        .find(|(_, d, _)| d == depend)
+                        .map(|(index, _, _)| index),
+                    // Без согласованного набора берём самую новую подходящую версию
+                    Err(_) => plugins
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, p)| *depend == p.info.bundle)
+                        .max_by(|(_, a), (_, b)| a.info.bundle.version.cmp(&b.info.bundle.version))
+                        .map(|(index, _)| index),
+                };
+
+                edges.push(DependencyEdge {
+                    from: plugin.info.bundle.clone(),
+                    depend: depend.clone(),
+                    optional,
+                    resolved: resolved.map(|index| plugins[index].info.bundle.clone()),
+                });
+            }
+
+            nodes.push(DependencyNode {
+                bundle: plugin.info.bundle.clone(),
+                is_load: plugin.is_load,
+                error: resolution.err().map(|e| e.to_string()),
+            });
+        }
+
+        Self { nodes, edges }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n    node [shape=box];\n");
+
+        for node in self.nodes.iter() {
+            let color = match (node.is_load, &node.error) {
+                (true, _) => "palegreen",
+                (false, Some(_)) => "lightpink",
+                (false, None) => "white",
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\\n{}\", style=filled, fillcolor={color}];",
+                escape(&node.bundle.to_string()),
+                escape(&node.bundle.id),
+                node.bundle.version
+            );
+        }
+
+        for edge in self.edges.iter() {
+            let style = match edge.optional {
+                true => "dashed",
+                false => "solid",
+            };
+            let to = match &edge.resolved {
+                Some(bundle) => bundle.to_string(),
+                // Неразрешённая зависимость отображается отдельным узлом
+                None => {
+                    let depend = edge.depend.to_string();
+                    let _ = writeln!(
+                        dot,
+                        "    \"{}\" [shape=ellipse, color=red];",
+                        escape(&depend)
+                    );
+                    depend
+                }
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\", style={style}];",
+                escape(&edge.from.to_string()),
+                escape(&to),
+                escape(&edge.depend.version.to_string())
+            );
+        }
+
+        dot.push('}');
+        dot
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        DependencyGraph::new(&self.state().plugins)
+    }
+}
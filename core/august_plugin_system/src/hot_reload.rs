@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, Receiver},
+        Mutex,
+    },
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    utils::{HotReloadError, ReloadPluginError},
+    Bundle, Info, Loader, Plugin,
+};
+
+type ReloadHandler = Box<dyn FnMut(&Bundle, &Result<(), ReloadPluginError>) + Send>;
+
+struct Watched {
+    path: PathBuf,
+    snapshot: PathBuf,
+}
+
+pub(crate) struct HotReload {
+    watcher: RecommendedWatcher,
+    receiver: Mutex<Receiver<notify::Result<Event>>>,
+    root: PathBuf,
+    watched: HashMap<Bundle, Watched>,
+    on_reload: Mutex<ReloadHandler>,
+}
+
+impl HotReload {
+    fn new(on_reload: ReloadHandler) -> Result<Self, HotReloadError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let (sender, receiver) = channel();
+        let watcher = notify::recommended_watcher(sender)?;
+
+        // Копии плагинов, к которым откатываемся при неудачной перезагрузке
+        let root = std::env::temp_dir().join("august").join(format!(
+            "hot-reload-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        Ok(Self {
+            watcher,
+            receiver: Mutex::new(receiver),
+            root,
+            watched: HashMap::new(),
+            on_reload: Mutex::new(on_reload),
+        })
+    }
+
+    pub(crate) fn snapshot(&self, bundle: &Bundle) -> Option<&Path> {
+        self.watched
+            .get(bundle)
+            .map(|watched| watched.snapshot.as_path())
+    }
+
+    // Отслеживаем новые плагины и забываем удалённые
+    fn sync<O: Send + Sync, I: Info>(
+        &mut self,
+        plugins: &[Plugin<'_, O, I>],
+    ) -> Result<(), HotReloadError> {
+        let removed: Vec<Bundle> = self
+            .watched
+            .keys()
+            .filter(|bundle| !plugins.iter().any(|plugin| **bundle == *plugin))
+            .cloned()
+            .collect();
+
+        for bundle in removed {
+            let watched = self.watched.remove(&bundle).unwrap();
+            let _ = self.watcher.unwatch(&watched.path);
+            let _ = std::fs::remove_dir_all(&watched.snapshot);
+        }
+
+        for plugin in plugins {
+            let bundle = &plugin.info.bundle;
+            if self.watched.contains_key(bundle) {
+                continue;
+            }
+
+            let path = plugin.info.path.clone();
+            let snapshot = self.root.join(bundle.to_string());
+            copy_dir(&path, &snapshot)?;
+
+            self.watcher.watch(&path, RecursiveMode::Recursive)?;
+            self.watched
+                .insert(bundle.clone(), Watched { path, snapshot });
+        }
+
+        Ok(())
+    }
+
+    // Плагины, в каталогах которых произошли изменения
+    fn changed(&self) -> Vec<Bundle> {
+        let mut result = vec![];
+
+        let receiver = self.receiver.lock().unwrap();
+        for event in receiver.try_iter().filter_map(|event| event.ok()) {
+            if let EventKind::Access(_) = event.kind {
+                continue;
+            }
+
+            for path in event.paths.iter() {
+                let bundle = self
+                    .watched
+                    .iter()
+                    .find(|(_, watched)| path.starts_with(&watched.path))
+                    .map(|(bundle, _)| bundle);
+
+                if let Some(bundle) = bundle {
+                    if !result.contains(bundle) {
+                        result.push(bundle.clone());
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn refresh(&self, bundle: &Bundle) -> Result<(), HotReloadError> {
+        if let Some(watched) = self.watched.get(bundle) {
+            std::fs::remove_dir_all(&watched.snapshot)?;
+            copy_dir(&watched.path, &watched.snapshot)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for HotReload {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        match entry.file_type()?.is_dir() {
+            true => copy_dir(&entry.path(), &target)?,
+            false => {
+                std::fs::copy(entry.path(), target)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
+    pub fn enable_hot_reload<F>(&mut self, on_reload: F) -> Result<(), HotReloadError>
+    where
+        F: FnMut(&Bundle, &Result<(), ReloadPluginError>) + Send + 'static,
+    {
+        let mut hot_reload = HotReload::new(Box::new(on_reload))?;
+        hot_reload.sync(&self.state().plugins)?;
+
+        self.hot_reload = Some(hot_reload);
+        Ok(())
+    }
+
+    pub fn disable_hot_reload(&mut self) {
+        self.hot_reload = None;
+    }
+
+    pub const fn is_hot_reload(&self) -> bool {
+        self.hot_reload.is_some()
+    }
+
+    // Перезагружает изменённые плагины. Вызывается из потока загрузчика,
+    // возвращает успешно перезагруженные плагины.
+    pub fn poll_hot_reload(&mut self) -> Result<Vec<Bundle>, HotReloadError> {
+        let inner = self.inner().clone();
+        let changed = match self.hot_reload.as_mut() {
+            Some(hot_reload) => {
+                hot_reload.sync(&inner.state.read_blocking().plugins)?;
+                hot_reload.changed()
+            }
+            None => return Ok(vec![]),
+        };
+
+        let mut reloaded = vec![];
+        for bundle in changed {
+            let is_load = self
+                .get_plugin_by_bundle(&bundle)
+                .is_some_and(|plugin| plugin.is_load);
+            if !is_load {
+                continue;
+            }
+
+            let result = self.reload_plugin_by_bundle(&bundle);
+
+            let hot_reload = self.hot_reload.as_mut().unwrap();
+            if result.is_ok() {
+                hot_reload.refresh(&bundle)?;
+                reloaded.push(bundle.clone());
+            }
+            (hot_reload.on_reload.lock().unwrap())(&bundle, &result);
+        }
+
+        Ok(reloaded)
+    }
+}
@@ -3,10 +3,14 @@ pub mod utils;
 
 mod api;
 mod bundle;
+mod event;
+mod graph;
 mod info;
 mod loader;
 mod manager;
+mod naming;
 mod plugin;
+mod resolver;
 
 pub mod function;
 pub mod variable;
@@ -16,17 +20,29 @@ use std::sync::Arc;
 pub use api::*;
 pub use bundle::*;
 pub use context::*;
+pub use event::*;
+pub use graph::*;
 pub use info::*;
 pub use loader::*;
 pub use manager::*;
+pub use naming::*;
 pub use plugin::*;
+pub use resolver::DependencyConflict;
 
 use function::{Function, Request};
 
 pub type Registry<O> = Vec<Arc<dyn Function<Output = O>>>;
 pub type Requests = Vec<Request>;
 
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+#[cfg(feature = "hot-reload")]
+pub(crate) use hot_reload::HotReload;
+
+#[cfg(feature = "discover")]
+mod discover;
+#[cfg(feature = "discover")]
+pub use discover::*;
+
 #[cfg(feature = "derive")]
-extern crate codegen;
-#[cfg(feature = "derive")]
-pub mod codegen;
+pub use codegen::{function, functions, requests};
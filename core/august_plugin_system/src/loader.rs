@@ -1,35 +1,133 @@
+use std::sync::{Arc, OnceLock, Weak};
+
 use rayon::prelude::{
     IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
     IntoParallelRefMutIterator, ParallelIterator,
 };
 use semver::Version;
 
+#[cfg(feature = "integrity")]
+use crate::utils::IntegrityPolicy;
+#[cfg(feature = "signing")]
+use crate::utils::TrustStore;
 use crate::{
+    resolver,
     utils::{
-        LoadPluginError, PluginCallRequestError, Ptr, RegisterManagerError, RegisterPluginError,
-        StopLoaderError, UnloadPluginError, UnregisterManagerError, UnregisterPluginError,
+        HeldLocks, LoadPluginError, LoaderMut, LoaderRef, Lock, PluginCallRequestError,
+        RegisterManagerError, RegisterPluginError, ReloadPluginError, StopLoaderError,
+        UnloadPluginError, UnregisterManagerError, UnregisterPluginError, ValidateLoaderError,
     },
     variable::Variable,
-    Bundle, Info, LoaderContext, Manager, Plugin, PluginInfo, Registry, Requests,
+    Bundle, BundleNaming, Events, FilenameNaming, Info, LoaderContext, LoaderEvent, Manager,
+    Plugin, PluginInfo, Registry, Requests, Subscription,
 };
 
+/// Загрузчик плагинов.
+///
+/// Методы получения (`get_plugin`, `get_plugins`, `get_registry` и т.п.) возвращают
+/// стражей [`LoaderRef`]/[`LoaderMut`], которые удерживают блокировку загрузчика.
+/// Пока страж жив, методы с `&mut self` недоступны, поэтому его нужно отпустить
+/// до изменения загрузчика.
 pub struct Loader<'a, O: Send + Sync, I: Info> {
+    // Создаётся при первом обращении, чтобы `new` оставался `const`
+    inner: OnceLock<Arc<LoaderInner<'a, O, I>>>,
+    #[cfg(feature = "hot-reload")]
+    pub(crate) hot_reload: Option<crate::HotReload>,
+}
+
+// Состояние загрузчика, разделяемое с Api плагинов
+pub(crate) struct LoaderInner<'a, O: Send + Sync, I: Info> {
+    pub(crate) state: Lock<LoaderState<'a, O, I>>,
+    pub(crate) registry: Lock<Registry<O>>,
+    pub(crate) requests: Lock<Requests>,
+}
+
+pub(crate) struct LoaderState<'a, O: Send + Sync, I: Info> {
+    pub(crate) handle: Weak<LoaderInner<'a, O, I>>,
     pub(crate) managers: Vec<Box<dyn Manager<'a, O, I>>>,
-    pub(crate) registry: Registry<O>,
-    pub(crate) requests: Requests,
     pub(crate) plugins: Vec<Plugin<'a, O, I>>,
+    pub(crate) events: Events,
+    #[cfg(feature = "integrity")]
+    pub(crate) integrity: IntegrityPolicy,
+    #[cfg(feature = "signing")]
+    pub(crate) trust_store: Option<TrustStore>,
+    pub(crate) naming: Box<dyn BundleNaming>,
+}
+
+// Плагины с одним ID, выбранные под общей блокировкой
+pub struct PluginsMut<'g, 'a, O: Send + Sync, I: Info> {
+    plugins: LoaderMut<'g, Vec<Plugin<'a, O, I>>>,
+    indexes: Vec<usize>,
+}
+
+impl<'g, 'a, O: Send + Sync, I: Info> PluginsMut<'g, 'a, O, I> {
+    fn new(loader: LoaderMut<'g, LoaderState<'a, O, I>>, indexes: Vec<usize>) -> Self {
+        Self {
+            plugins: LoaderMut::map(loader, |loader| &mut loader.plugins),
+            indexes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Plugin<'a, O, I>> {
+        self.indexes.iter().map(|index| &self.plugins[*index])
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Plugin<'a, O, I>> {
+        let indexes = &self.indexes;
+        self.plugins
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| indexes.contains(index))
+            .map(|(_, plugin)| plugin)
+    }
 }
 
 impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
     pub const fn new() -> Self {
         Self {
-            managers: vec![],
-            registry: vec![],
-            requests: vec![],
-            plugins: vec![],
+            inner: OnceLock::new(),
+            #[cfg(feature = "hot-reload")]
+            hot_reload: None,
         }
     }
 
+    pub(crate) fn inner(&self) -> &Arc<LoaderInner<'a, O, I>> {
+        self.inner.get_or_init(|| {
+            Arc::new_cyclic(|handle| LoaderInner {
+                state: Lock::new(LoaderState {
+                    handle: handle.clone(),
+                    managers: vec![],
+                    plugins: vec![],
+                    events: Events::new(),
+                    #[cfg(feature = "integrity")]
+                    integrity: IntegrityPolicy::Off,
+                    #[cfg(feature = "signing")]
+                    trust_store: None,
+                    naming: Box::new(FilenameNaming),
+                }),
+                registry: Lock::new(vec![]),
+                requests: Lock::new(vec![]),
+            })
+        })
+    }
+
+    // Хост не может удерживать блокировку загрузчика между вызовами его методов
+    pub(crate) fn state(&self) -> LoaderRef<'_, LoaderState<'a, O, I>> {
+        self.inner().state.read_blocking()
+    }
+
+    pub(crate) fn state_mut(&mut self) -> LoaderMut<'_, LoaderState<'a, O, I>> {
+        self.inner().state.write_blocking()
+    }
+
     pub fn context<FO, R>(&mut self, f: FO) -> R
     where
         FO: FnOnce(LoaderContext<'a, '_, O, I>) -> R,
@@ -38,8 +136,9 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
     }
 
     pub fn stop(&mut self) -> Result<(), StopLoaderError> {
-        private_loader::stop_plugins(self)?;
-        private_loader::stop_managers(self)?;
+        let mut loader = self.state_mut();
+        private_loader::stop_plugins(&mut loader)?;
+        private_loader::stop_managers(&mut loader)?;
         Ok(())
     }
 
@@ -47,17 +146,599 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
     where
         M: Manager<'a, O, I> + 'static,
     {
-        private_loader::register_manager(self, Box::new(manager))
+        self.state_mut().register_manager(manager)
     }
 
+    /// Регистрирует менеджер без проверки занятости формата.
+    ///
+    /// # Safety
+    ///
+    /// Менеджер с тем же форматом не должен быть зарегистрирован, иначе плагины
+    /// формата будут загружаться и выгружаться разными менеджерами.
     pub unsafe fn forced_register_manager(
         &mut self,
         manager: Box<dyn Manager<'a, O, I>>,
     ) -> Result<(), RegisterManagerError> {
-        private_loader::forced_register_manager(self, manager)
+        private_loader::forced_register_manager(&mut self.state_mut(), manager)
     }
 
     pub fn register_managers<M>(&mut self, managers: M) -> Result<(), RegisterManagerError>
+    where
+        M: IntoIterator<Item = Box<dyn Manager<'a, O, I>>>,
+    {
+        self.state_mut().register_managers(managers)
+    }
+
+    pub fn par_register_managers<M>(&mut self, managers: M) -> Result<(), RegisterManagerError>
+    where
+        M: IntoParallelIterator<Item = Box<dyn Manager<'a, O, I>>>,
+    {
+        self.state_mut().par_register_managers(managers)
+    }
+
+    pub fn unregister_manager(&mut self, format: &str) -> Result<(), UnregisterManagerError> {
+        self.state_mut().unregister_manager(format)
+    }
+
+    /// Удаляет менеджер по индексу, не трогая его плагины.
+    ///
+    /// # Safety
+    ///
+    /// `index` должен быть меньше числа менеджеров. Плагины формата менеджера
+    /// должны быть выгружены и удалены заранее: код, загруженный менеджером,
+    /// становится недействительным вместе с ним.
+    pub unsafe fn forced_unregister_manager(
+        &mut self,
+        index: usize,
+    ) -> Result<(), UnregisterManagerError> {
+        let mut loader = self.state_mut();
+        let loader = &mut *loader;
+        private_loader::forced_unregister_manager(&mut loader.managers, &loader.events, index)
+    }
+
+    pub fn get_manager_ref(
+        &self,
+        format: &str,
+    ) -> Option<LoaderRef<'_, Box<dyn Manager<'a, O, I>>>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.get_manager_ref(format))
+    }
+
+    pub fn par_get_manager_ref(
+        &self,
+        format: &str,
+    ) -> Option<LoaderRef<'_, Box<dyn Manager<'a, O, I>>>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.par_get_manager_ref(format))
+    }
+
+    pub fn get_manager_mut(
+        &mut self,
+        format: &str,
+    ) -> Option<LoaderMut<'_, Box<dyn Manager<'a, O, I>>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| loader.get_manager_mut(format))
+    }
+
+    pub fn par_get_manager_mut(
+        &mut self,
+        format: &str,
+    ) -> Option<LoaderMut<'_, Box<dyn Manager<'a, O, I>>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| {
+            loader.par_get_manager_mut(format)
+        })
+    }
+
+    // Способ именования применяется к последующим регистрациям
+    pub fn set_bundle_naming<N>(&mut self, naming: N)
+    where
+        N: BundleNaming + 'static,
+    {
+        self.state_mut().naming = Box::new(naming);
+    }
+
+    // Политика проверки манифеста для последующих регистраций
+    #[cfg(feature = "integrity")]
+    pub fn set_integrity_policy(&mut self, policy: IntegrityPolicy) {
+        self.state_mut().integrity = policy;
+    }
+
+    #[cfg(feature = "integrity")]
+    pub fn integrity_policy(&self) -> IntegrityPolicy {
+        self.state().integrity
+    }
+
+    // С хранилищем ключей регистрируются только подписанные доверенными авторами бандлы
+    #[cfg(feature = "signing")]
+    pub fn set_trust_store(&mut self, trust_store: TrustStore) {
+        self.state_mut().trust_store = Some(trust_store);
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn remove_trust_store(&mut self) -> Option<TrustStore> {
+        self.state_mut().trust_store.take()
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn trust_store(&self) -> Option<LoaderRef<'_, TrustStore>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.trust_store.as_ref())
+    }
+
+    pub fn register_plugin(&mut self, path: &str) -> Result<Bundle, RegisterPluginError> {
+        self.state_mut().register_plugin(path)
+    }
+
+    /// Добавляет плагин, минуя менеджер, проверку целостности и именования.
+    ///
+    /// # Safety
+    ///
+    /// Бандл не должен быть зарегистрирован, а менеджер его формата должен
+    /// уметь загрузить плагин без предварительного `register_plugin`.
+    pub unsafe fn forced_register_plugin(
+        &mut self,
+        plugin_info: PluginInfo<I>,
+    ) -> Result<Bundle, RegisterPluginError> {
+        private_loader::forced_register_plugin(&mut self.state_mut(), plugin_info)
+    }
+
+    pub fn register_plugins<'b, P>(&mut self, paths: P) -> Result<Vec<Bundle>, RegisterPluginError>
+    where
+        P: IntoIterator<Item = &'b str>,
+    {
+        self.state_mut().register_plugins(paths)
+    }
+
+    pub fn par_register_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<Vec<Bundle>, RegisterPluginError>
+    where
+        P: IntoParallelIterator<Item = &'b str>,
+    {
+        self.state_mut().par_register_plugins(paths)
+    }
+
+    pub fn unregister_plugin(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Result<(), UnregisterPluginError> {
+        self.state_mut().unregister_plugin(id, version)
+    }
+
+    pub fn unregister_plugin_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Result<(), UnregisterPluginError> {
+        self.state_mut().unregister_plugin_by_bundle(bundle)
+    }
+
+    pub fn par_unregister_plugin_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Result<(), UnregisterPluginError> {
+        self.state_mut().par_unregister_plugin_by_bundle(bundle)
+    }
+
+    /// Удаляет плагин по индексу без проверки зависимостей.
+    ///
+    /// # Safety
+    ///
+    /// `index` должен быть меньше числа плагинов, а сам плагин выгружен:
+    /// загруженные плагины не должны использовать его как зависимость.
+    pub unsafe fn forced_unregister_plugin(
+        &mut self,
+        index: usize,
+    ) -> Result<(), UnregisterPluginError> {
+        private_loader::forced_unregister_plugin(&mut self.state_mut(), index)
+    }
+
+    pub fn unload_plugin(&mut self, id: &str, version: &Version) -> Result<(), UnloadPluginError> {
+        self.state_mut().unload_plugin(id, version)
+    }
+
+    pub fn par_unload_plugin(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Result<(), UnloadPluginError> {
+        self.state_mut().par_unload_plugin(id, version)
+    }
+
+    pub fn unload_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), UnloadPluginError> {
+        self.state_mut().unload_plugin_by_bundle(bundle)
+    }
+
+    pub fn par_unload_plugin_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Result<(), UnloadPluginError> {
+        self.state_mut().par_unload_plugin_by_bundle(bundle)
+    }
+
+    /// Выгружает плагин по индексу без проверки зависимостей.
+    ///
+    /// # Safety
+    ///
+    /// `index` должен быть меньше числа плагинов. Загруженные плагины не должны
+    /// использовать его как зависимость: их `Api` хранит функции плагина.
+    pub unsafe fn forced_unload_plugin(&mut self, index: usize) -> Result<(), UnloadPluginError> {
+        private_loader::forced_unload_plugin(&mut self.state_mut(), index)
+    }
+
+    pub fn get_plugin(
+        &self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderRef<'_, Plugin<'a, O, I>>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.get_plugin(id, version))
+    }
+
+    pub fn par_get_plugin(
+        &self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderRef<'_, Plugin<'a, O, I>>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.par_get_plugin(id, version))
+    }
+
+    pub fn get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<LoaderRef<'_, Plugin<'a, O, I>>> {
+        LoaderRef::filter_map(self.state(), |loader| loader.get_plugin_by_bundle(bundle))
+    }
+
+    pub fn par_get_plugin_by_bundle(
+        &self,
+        bundle: &Bundle,
+    ) -> Option<LoaderRef<'_, Plugin<'a, O, I>>> {
+        LoaderRef::filter_map(self.state(), |loader| {
+            loader.par_get_plugin_by_bundle(bundle)
+        })
+    }
+
+    pub fn get_plugin_mut(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderMut<'_, Plugin<'a, O, I>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| {
+            loader.get_plugin_mut(id, version)
+        })
+    }
+
+    pub fn par_get_plugin_mut(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Option<LoaderMut<'_, Plugin<'a, O, I>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| {
+            loader.par_get_plugin_mut(id, version)
+        })
+    }
+
+    pub fn get_plugin_mut_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Option<LoaderMut<'_, Plugin<'a, O, I>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| {
+            loader.get_plugin_mut_by_bundle(bundle)
+        })
+    }
+
+    pub fn par_get_plugin_mut_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Option<LoaderMut<'_, Plugin<'a, O, I>>> {
+        LoaderMut::filter_map(self.state_mut(), |loader| {
+            loader.par_get_plugin_mut_by_bundle(bundle)
+        })
+    }
+
+    pub fn get_plugins_by_id(&self, id: &str) -> Vec<LoaderRef<'_, Plugin<'a, O, I>>> {
+        let loader = self.state();
+        let indexes = loader.get_plugins_by_id(id);
+        self.inner().plugins_ref(&loader, indexes)
+    }
+
+    pub fn par_get_plugins_by_id(&self, id: &str) -> Vec<LoaderRef<'_, Plugin<'a, O, I>>> {
+        let loader = self.state();
+        let indexes = loader.par_get_plugins_by_id(id);
+        self.inner().plugins_ref(&loader, indexes)
+    }
+
+    pub fn get_plugins_by_id_mut(&mut self, id: &str) -> PluginsMut<'_, 'a, O, I> {
+        let loader = self.state_mut();
+        let indexes = loader.get_plugins_by_id(id);
+        PluginsMut::new(loader, indexes)
+    }
+
+    pub fn par_get_plugins_by_id_mut(&mut self, id: &str) -> PluginsMut<'_, 'a, O, I> {
+        let loader = self.state_mut();
+        let indexes = loader.par_get_plugins_by_id(id);
+        PluginsMut::new(loader, indexes)
+    }
+
+    pub fn subscribe<F>(&mut self, handler: F) -> Subscription
+    where
+        F: Fn(&LoaderEvent) + Send + Sync + 'static,
+    {
+        self.state_mut().events.subscribe(handler)
+    }
+
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.state_mut().events.unsubscribe(subscription)
+    }
+
+    pub fn get_plugins(&self) -> LoaderRef<'_, Vec<Plugin<'a, O, I>>> {
+        LoaderRef::map(self.state(), |loader| &loader.plugins)
+    }
+
+    pub fn get_registry(&self) -> LoaderRef<'_, Registry<O>> {
+        self.inner().registry.read_blocking()
+    }
+
+    pub fn get_requests(&self) -> LoaderRef<'_, Requests> {
+        self.inner().requests.read_blocking()
+    }
+
+    // Проверяет граф зарегистрированных плагинов без их загрузки
+    pub fn validate(&self) -> Result<(), ValidateLoaderError> {
+        let loader = self.state();
+        let mut errors: Vec<_> = (0..loader.plugins.len())
+            .filter_map(|index| {
+                resolver::resolve(&loader.plugins, &[index])
+                    .err()
+                    .map(|e| (loader.plugins[index].info.bundle.clone(), e))
+            })
+            .collect();
+
+        // Каждый корень может разрешаться отдельно, но требовать несовместимые
+        // версии общей зависимости
+        let roots = resolver::roots(&loader.plugins);
+        if errors.is_empty() {
+            if let Err(error) = resolver::resolve(&loader.plugins, &roots) {
+                let bundle = match &error {
+                    LoadPluginError::DependencyConflict(conflict) => conflict.plugin().clone(),
+                    _ => loader.plugins[roots[0]].info.bundle.clone(),
+                };
+                errors.push((bundle, error));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidateLoaderError::InvalidPlugins(errors)),
+        }
+    }
+
+    pub fn call_request(
+        &self,
+        name: &str,
+        args: &[Variable],
+    ) -> Result<Vec<O>, PluginCallRequestError> {
+        self.state().call_request(name, args)
+    }
+
+    pub fn par_call_request(
+        &self,
+        name: &str,
+        args: &[Variable],
+    ) -> Result<Vec<O>, PluginCallRequestError> {
+        self.state().par_call_request(name, args)
+    }
+}
+
+impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
+    pub fn load_plugin(&mut self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
+        self.state_mut().load_plugin(id, version)
+    }
+
+    pub fn par_load_plugin(&mut self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
+        self.state_mut().par_load_plugin(id, version)
+    }
+
+    pub fn load_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), LoadPluginError> {
+        self.state_mut().load_plugin_by_bundle(bundle)
+    }
+
+    pub fn par_load_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), LoadPluginError> {
+        self.state_mut().par_load_plugin_by_bundle(bundle)
+    }
+
+    /// Перезагружает плагин вместе с загруженными зависимыми от него плагинами.
+    ///
+    /// Откат возможен только для плагина, за которым следит горячая перезагрузка:
+    /// она хранит копию плагина с начала наблюдения или последней удачной
+    /// горячей перезагрузки. Без неё плагин, который не удалось загрузить,
+    /// остаётся выгруженным, остальные плагины загружаются снова, а ошибка
+    /// возвращается.
+    pub fn reload_plugin(&mut self, id: &str, version: &Version) -> Result<(), ReloadPluginError> {
+        let index = self
+            .state()
+            .plugins
+            .iter()
+            .position(|plugin| *plugin == (id, version))
+            .ok_or(ReloadPluginError::NotFound)?;
+        self.forced_reload_plugin(index)
+    }
+
+    /// То же, что [`Loader::reload_plugin`].
+    pub fn reload_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), ReloadPluginError> {
+        let index = self
+            .state()
+            .plugins
+            .iter()
+            .position(|plugin| *plugin == *bundle)
+            .ok_or(ReloadPluginError::NotFound)?;
+        self.forced_reload_plugin(index)
+    }
+
+    fn forced_reload_plugin(&mut self, index: usize) -> Result<(), ReloadPluginError> {
+        #[cfg(feature = "hot-reload")]
+        let snapshot = self.hot_reload.as_ref().and_then(|hot_reload| {
+            hot_reload
+                .snapshot(&self.state().plugins[index].info.bundle)
+                .map(|path| path.to_path_buf())
+        });
+        #[cfg(not(feature = "hot-reload"))]
+        let snapshot: Option<std::path::PathBuf> = None;
+
+        private_loader::reload_plugin(&mut self.state_mut(), index, snapshot.as_deref())
+    }
+
+    /// Загружает плагин по индексу с заданными зависимостями, минуя их подбор.
+    ///
+    /// # Safety
+    ///
+    /// `index` должен быть меньше числа плагинов. Зависимости из `depends`
+    /// должны быть загружены и удовлетворять требованиям плагина; наличие
+    /// запрашиваемых функций не проверяется.
+    pub unsafe fn forced_load_plugin(
+        &mut self,
+        index: usize,
+        depends: Vec<(Bundle, bool)>,
+    ) -> Result<(), LoadPluginError> {
+        let mut loader = self.state_mut();
+        let bundle = loader.plugins[index].info.bundle.clone();
+        loader
+            .events
+            .emit(LoaderEvent::PluginLoading { bundle: &bundle });
+
+        let result = private_loader::forced_load_plugin(&mut loader, index, depends);
+        private_loader::emit_load_result(&loader.events, &bundle, result)
+    }
+
+    pub fn load_plugin_now(
+        &mut self,
+        path: &str,
+    ) -> Result<Bundle, (Option<RegisterPluginError>, Option<LoadPluginError>)> {
+        self.state_mut().load_plugin_now(path)
+    }
+
+    pub fn load_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<Vec<Bundle>, (Option<RegisterPluginError>, Option<LoadPluginError>)>
+    where
+        P: IntoIterator<Item = &'b str>,
+    {
+        self.state_mut().load_plugins(paths)
+    }
+
+    // Менеджеры загружают плагины под монопольной блокировкой загрузчика,
+    // поэтому параллельной остаётся только регистрация
+    #[deprecated(
+        note = "plugins are registered in parallel but loaded sequentially; use `load_plugins`"
+    )]
+    pub fn par_load_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<Vec<Bundle>, (Option<RegisterPluginError>, Option<LoadPluginError>)>
+    where
+        P: IntoParallelIterator<Item = &'b str>,
+    {
+        self.state_mut().par_load_plugins(paths)
+    }
+
+    pub fn load_only_used_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<
+        Vec<Bundle>,
+        (
+            Option<RegisterPluginError>,
+            Option<UnregisterPluginError>,
+            Option<LoadPluginError>,
+        ),
+    >
+    where
+        P: IntoIterator<Item = &'b str>,
+    {
+        self.state_mut().load_only_used_plugins(paths)
+    }
+
+    // Менеджеры загружают плагины под монопольной блокировкой загрузчика,
+    // поэтому параллельной остаётся только регистрация
+    #[deprecated(
+        note = "plugins are registered in parallel but loaded sequentially; use `load_only_used_plugins`"
+    )]
+    pub fn par_load_only_used_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<
+        Vec<Bundle>,
+        (
+            Option<RegisterPluginError>,
+            Option<UnregisterPluginError>,
+            Option<LoadPluginError>,
+        ),
+    >
+    where
+        P: IntoParallelIterator<Item = &'b str>,
+    {
+        self.state_mut().par_load_only_used_plugins(paths)
+    }
+}
+
+impl<O: Send + Sync, I: Info> Drop for Loader<'_, O, I> {
+    fn drop(&mut self) {
+        self.stop().unwrap();
+    }
+}
+
+impl<'a, O: Send + Sync, I: Info> LoaderInner<'a, O, I> {
+    pub(crate) fn get_plugins_by_id(
+        &self,
+        id: &str,
+    ) -> Option<Vec<LoaderRef<'_, Plugin<'a, O, I>>>> {
+        let loader = self.state.read()?;
+        let indexes = loader.get_plugins_by_id(id);
+        Some(self.plugins_ref(&loader, indexes))
+    }
+
+    pub(crate) fn par_get_plugins_by_id(
+        &self,
+        id: &str,
+    ) -> Option<Vec<LoaderRef<'_, Plugin<'a, O, I>>>> {
+        let loader = self.state.read()?;
+        let indexes = loader.par_get_plugins_by_id(id);
+        Some(self.plugins_ref(&loader, indexes))
+    }
+
+    // Каждая ссылка удерживает собственную блокировку на чтение
+    pub(crate) fn plugins_ref(
+        &self,
+        _loader: &LoaderRef<'_, LoaderState<'a, O, I>>,
+        indexes: Vec<usize>,
+    ) -> Vec<LoaderRef<'_, Plugin<'a, O, I>>> {
+        indexes
+            .into_iter()
+            .map(|index| {
+                LoaderRef::map(self.state.read_blocking(), |loader| {
+                    &loader.plugins[index]
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn get_plugins_by_id_mut(&self, id: &str) -> Option<PluginsMut<'_, 'a, O, I>> {
+        let loader = self.state.write()?;
+        let indexes = loader.get_plugins_by_id(id);
+        Some(PluginsMut::new(loader, indexes))
+    }
+
+    pub(crate) fn par_get_plugins_by_id_mut(&self, id: &str) -> Option<PluginsMut<'_, 'a, O, I>> {
+        let loader = self.state.write()?;
+        let indexes = loader.par_get_plugins_by_id(id);
+        Some(PluginsMut::new(loader, indexes))
+    }
+}
+
+impl<'a, O: Send + Sync, I: Info> LoaderState<'a, O, I> {
+    pub(crate) fn register_manager<M>(&mut self, manager: M) -> Result<(), RegisterManagerError>
+    where
+        M: Manager<'a, O, I> + 'static,
+    {
+        private_loader::register_manager(self, Box::new(manager))
+    }
+
+    pub(crate) fn register_managers<M>(&mut self, managers: M) -> Result<(), RegisterManagerError>
     where
         M: IntoIterator<Item = Box<dyn Manager<'a, O, I>>>,
     {
@@ -68,19 +749,45 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
         Ok(())
     }
 
-    pub fn par_register_managers<M>(&mut self, managers: M) -> Result<(), RegisterManagerError>
+    pub(crate) fn par_register_managers<M>(
+        &mut self,
+        managers: M,
+    ) -> Result<(), RegisterManagerError>
     where
         M: IntoParallelIterator<Item = Box<dyn Manager<'a, O, I>>>,
     {
-        let this = Ptr::new(self);
-        managers.into_par_iter().try_for_each(move |manager| {
-            private_loader::register_manager(this.as_mut(), manager)
-        })?;
+        let mut managers: Vec<_> = managers.into_par_iter().collect();
+
+        // Проверяем форматы до регистрации, чтобы не регистрировать менеджеры понапрасну
+        for (index, manager) in managers.iter().enumerate() {
+            let is_occupied =
+                self.managers.contains(manager) || managers[..index].contains(manager);
+
+            if is_occupied {
+                return Err(RegisterManagerError::AlreadyOccupiedFormat(
+                    manager.format().to_string(),
+                ));
+            }
+        }
+
+        managers
+            .par_iter_mut()
+            .try_for_each(|manager| manager.register_manager())?;
+
+        for manager in managers {
+            self.events.emit(LoaderEvent::ManagerRegistered {
+                format: manager.format(),
+            });
+            self.managers.push(manager);
+        }
 
         Ok(())
     }
 
-    pub fn unregister_manager(&mut self, format: &str) -> Result<(), UnregisterManagerError> {
+    pub(crate) fn unregister_manager(
+        &mut self,
+        format: &str,
+    ) -> Result<(), UnregisterManagerError> {
         let index = self
             .managers
             .iter()
@@ -94,47 +801,40 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
         private_loader::unregister_manager(self, index)
     }
 
-    pub unsafe fn forced_unregister_manager(
-        &mut self,
-        index: usize,
-    ) -> Result<(), UnregisterManagerError> {
-        private_loader::forced_unregister_manager(&mut self.managers, index)
-    }
-
-    pub fn get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'a, O, I>>> {
+    pub(crate) fn get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'a, O, I>>> {
         self.managers.iter().find(|m| m.format() == format)
     }
 
-    pub fn par_get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'a, O, I>>> {
+    pub(crate) fn par_get_manager_ref(&self, format: &str) -> Option<&Box<dyn Manager<'a, O, I>>> {
         self.managers
             .par_iter()
             .find_first(|m| m.format() == format)
     }
 
-    pub fn get_manager_mut(&mut self, format: &str) -> Option<&mut Box<dyn Manager<'a, O, I>>> {
+    pub(crate) fn get_manager_mut(
+        &mut self,
+        format: &str,
+    ) -> Option<&mut Box<dyn Manager<'a, O, I>>> {
         self.managers.iter_mut().find(|m| m.format() == format)
     }
 
-    pub fn par_get_manager_mut(&mut self, format: &str) -> Option<&mut Box<dyn Manager<'a, O, I>>> {
+    pub(crate) fn par_get_manager_mut(
+        &mut self,
+        format: &str,
+    ) -> Option<&mut Box<dyn Manager<'a, O, I>>> {
         self.managers
             .par_iter_mut()
             .find_first(|m| m.format() == format)
     }
 
-	//TODO: Добавить параллельную версию
-    pub fn register_plugin(&mut self, path: &str) -> Result<Bundle, RegisterPluginError> {
+    pub(crate) fn register_plugin(&mut self, path: &str) -> Result<Bundle, RegisterPluginError> {
         private_loader::register_plugin(self, path)
     }
 
-    pub unsafe fn forced_register_plugin(
+    pub(crate) fn register_plugins<'b, P>(
         &mut self,
-        manager: &mut Box<dyn Manager<'a, O, I>>,
-        plugin_info: PluginInfo<I>,
-    ) -> Result<Bundle, RegisterPluginError> {
-        private_loader::forced_register_plugin(&mut self.plugins, Ptr::new(manager), plugin_info)
-    }
-
-	pub fn register_plugins<'b, P>(&mut self, paths: P) -> Result<Vec<Bundle>, RegisterPluginError>
+        paths: P,
+    ) -> Result<Vec<Bundle>, RegisterPluginError>
     where
         P: IntoIterator<Item = &'b str>,
     {
@@ -144,22 +844,40 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .collect::<Result<Vec<_>, _>>()
     }
 
-    pub fn par_register_plugins<'b, P>(
+    pub(crate) fn par_register_plugins<'b, P>(
         &mut self,
         paths: P,
     ) -> Result<Vec<Bundle>, RegisterPluginError>
     where
         P: IntoParallelIterator<Item = &'b str>,
     {
-        let this = Ptr::new(self);
-
-        paths
+        // Проверка и распаковка бандлов идут параллельно,
+        // а регистрация менеджерами - последовательно
+        let prepared: Vec<_> = paths
             .into_par_iter()
-            .map(move |path| private_loader::register_plugin(this.as_mut(), path))
-            .collect::<Result<Vec<_>, _>>()
+            .map(|path| private_loader::prepare_plugin(self, path))
+            .collect();
+
+        let mut prepared = prepared.into_iter();
+        let mut bundles = vec![];
+
+        while let Some(plugin) = prepared.next() {
+            let result =
+                plugin.and_then(|plugin| private_loader::register_prepared_plugin(self, plugin));
+
+            match result {
+                Ok(bundle) => bundles.push(bundle),
+                Err(e) => {
+                    prepared.flatten().for_each(|plugin| plugin.remove_cache());
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(bundles)
     }
 
-    pub fn unregister_plugin(
+    pub(crate) fn unregister_plugin(
         &mut self,
         id: &str,
         version: &Version,
@@ -169,10 +887,10 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .iter()
             .position(|plugin| *plugin == (id, version))
             .ok_or(UnregisterPluginError::NotFound)?;
-        private_loader::unregister_plugin(&mut self.plugins, index)
+        private_loader::unregister_plugin(self, index)
     }
 
-    pub fn unregister_plugin_by_bundle(
+    pub(crate) fn unregister_plugin_by_bundle(
         &mut self,
         bundle: &Bundle,
     ) -> Result<(), UnregisterPluginError> {
@@ -181,10 +899,10 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .iter()
             .position(|plugin| *plugin == *bundle)
             .ok_or(UnregisterPluginError::NotFound)?;
-        private_loader::unregister_plugin(&mut self.plugins, index)
+        private_loader::unregister_plugin(self, index)
     }
 
-    pub fn par_unregister_plugin_by_bundle(
+    pub(crate) fn par_unregister_plugin_by_bundle(
         &mut self,
         bundle: &Bundle,
     ) -> Result<(), UnregisterPluginError> {
@@ -193,26 +911,23 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .par_iter()
             .position_first(|plugin| *plugin == *bundle)
             .ok_or(UnregisterPluginError::NotFound)?;
-        private_loader::unregister_plugin(&mut self.plugins, index)
+        private_loader::unregister_plugin(self, index)
     }
 
-    pub unsafe fn forced_unregister_plugin(
+    pub(crate) fn unload_plugin(
         &mut self,
-        index: usize,
-    ) -> Result<(), UnregisterPluginError> {
-        private_loader::forced_unregister_plugin(&mut self.plugins, index)
-    }
-
-    pub fn unload_plugin(&mut self, id: &str, version: &Version) -> Result<(), UnloadPluginError> {
+        id: &str,
+        version: &Version,
+    ) -> Result<(), UnloadPluginError> {
         let index = self
             .plugins
             .iter()
             .position(|plugin| *plugin == (id, version))
             .ok_or(UnloadPluginError::NotFound)?;
-        private_loader::unload_plugin(&mut self.plugins, index)
+        private_loader::unload_plugin(self, index)
     }
 
-    pub fn par_unload_plugin(
+    pub(crate) fn par_unload_plugin(
         &mut self,
         id: &str,
         version: &Version,
@@ -222,19 +937,22 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .par_iter()
             .position_first(|plugin| *plugin == (id, version))
             .ok_or(UnloadPluginError::NotFound)?;
-        private_loader::unload_plugin(&mut self.plugins, index)
+        private_loader::unload_plugin(self, index)
     }
 
-    pub fn unload_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), UnloadPluginError> {
+    pub(crate) fn unload_plugin_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Result<(), UnloadPluginError> {
         let index = self
             .plugins
             .iter()
             .position(|plugin| *plugin == *bundle)
             .ok_or(UnloadPluginError::NotFound)?;
-        private_loader::unload_plugin(&mut self.plugins, index)
+        private_loader::unload_plugin(self, index)
     }
 
-    pub fn par_unload_plugin_by_bundle(
+    pub(crate) fn par_unload_plugin_by_bundle(
         &mut self,
         bundle: &Bundle,
     ) -> Result<(), UnloadPluginError> {
@@ -243,40 +961,40 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .par_iter()
             .position_first(|plugin| *plugin == *bundle)
             .ok_or(UnloadPluginError::NotFound)?;
-        private_loader::unload_plugin(&mut self.plugins, index)
-    }
-
-    pub unsafe fn forced_unload_plugin(&mut self, index: usize) -> Result<(), UnloadPluginError> {
-        private_loader::forced_unload_plugin(&mut self.plugins, index)
+        private_loader::unload_plugin(self, index)
     }
 
-    pub fn get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'a, O, I>> {
+    pub(crate) fn get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'a, O, I>> {
         self.plugins.iter().find(|plugin| **plugin == (id, version))
     }
 
-    pub fn par_get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'a, O, I>> {
+    pub(crate) fn par_get_plugin(&self, id: &str, version: &Version) -> Option<&Plugin<'a, O, I>> {
         self.plugins
             .par_iter()
             .find_first(|plugin| **plugin == (id, version))
     }
 
-    pub fn get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'a, O, I>> {
+    pub(crate) fn get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'a, O, I>> {
         self.plugins.iter().find(|plugin| *plugin == bundle)
     }
 
-    pub fn par_get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'a, O, I>> {
+    pub(crate) fn par_get_plugin_by_bundle(&self, bundle: &Bundle) -> Option<&Plugin<'a, O, I>> {
         self.plugins
             .par_iter()
             .find_first(|plugin| *plugin == bundle)
     }
 
-    pub fn get_plugin_mut(&mut self, id: &str, version: &Version) -> Option<&mut Plugin<'a, O, I>> {
+    pub(crate) fn get_plugin_mut(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Option<&mut Plugin<'a, O, I>> {
         self.plugins
             .iter_mut()
             .find(|plugin| **plugin == (id, version))
     }
 
-    pub fn par_get_plugin_mut(
+    pub(crate) fn par_get_plugin_mut(
         &mut self,
         id: &str,
         version: &Version,
@@ -286,11 +1004,14 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .find_first(|plugin| **plugin == (id, version))
     }
 
-    pub fn get_plugin_mut_by_bundle(&mut self, bundle: &Bundle) -> Option<&mut Plugin<'a, O, I>> {
+    pub(crate) fn get_plugin_mut_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Option<&mut Plugin<'a, O, I>> {
         self.plugins.iter_mut().find(|plugin| *plugin == bundle)
     }
 
-    pub fn par_get_plugin_mut_by_bundle(
+    pub(crate) fn par_get_plugin_mut_by_bundle(
         &mut self,
         bundle: &Bundle,
     ) -> Option<&mut Plugin<'a, O, I>> {
@@ -299,50 +1020,25 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .find_first(|plugin| *plugin == bundle)
     }
 
-    pub fn get_plugins_by_id(&self, id: &str) -> Vec<&Plugin<'a, O, I>> {
+    pub(crate) fn get_plugins_by_id(&self, id: &str) -> Vec<usize> {
         self.plugins
             .iter()
-            .filter(|plugin| plugin.info.bundle.id == id)
+            .enumerate()
+            .filter(|(_, plugin)| plugin.info.bundle.id == id)
+            .map(|(index, _)| index)
             .collect()
     }
 
-    pub fn par_get_plugins_by_id(&self, id: &str) -> Vec<&Plugin<'a, O, I>> {
+    pub(crate) fn par_get_plugins_by_id(&self, id: &str) -> Vec<usize> {
         self.plugins
             .par_iter()
-            .filter(|plugin| plugin.info.bundle.id == id)
-            .collect()
-    }
-
-    pub fn get_plugins_by_id_mut(&mut self, id: &str) -> Vec<&mut Plugin<'a, O, I>> {
-        self.plugins
-            .iter_mut()
-            .filter(|plugin| plugin.info.bundle.id == id)
-            .collect()
-    }
-
-    pub fn par_get_plugins_by_id_mut(&mut self, id: &str) -> Vec<&mut Plugin<'a, O, I>> {
-        self.plugins
-            .par_iter_mut()
-            .filter(|plugin| plugin.info.bundle.id == id)
+            .enumerate()
+            .filter(|(_, plugin)| plugin.info.bundle.id == id)
+            .map(|(index, _)| index)
             .collect()
     }
 
-    //TODO: Добавить функции для отслеживания загрузки и выгрузки
-    //		менеджеров или плагинов
-
-    pub const fn get_plugins(&self) -> &Vec<Plugin<'a, O, I>> {
-        &self.plugins
-    }
-
-    pub const fn get_registry(&self) -> &Registry<O> {
-        &self.registry
-    }
-
-    pub const fn get_requests(&self) -> &Requests {
-        &self.requests
-    }
-
-    pub fn call_request(
+    pub(crate) fn call_request(
         &self,
         name: &str,
         args: &[Variable],
@@ -363,7 +1059,7 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             .collect()
     }
 
-    pub fn par_call_request(
+    pub(crate) fn par_call_request(
         &self,
         name: &str,
         args: &[Variable],
@@ -384,13 +1080,18 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
             })
             .collect();
 
+        // Функции выполняются под блокировкой вызвавшего потока
+        let held = HeldLocks::current();
         requests
             .into_par_iter()
             .map(|requests| {
                 requests
                     .par_iter()
                     .find_map_first(|request| match request.name() == name {
-                        true => Some(request.call(args)),
+                        true => {
+                            let _held = held.enter();
+                            Some(request.call(args))
+                        }
                         false => None,
                     })
                     .ok_or(PluginCallRequestError::NotFound)
@@ -399,8 +1100,12 @@ impl<'a, O: Send + Sync, I: Info> Loader<'a, O, I> {
     }
 }
 
-impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
-    pub fn load_plugin(&mut self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
+impl<O: Send + Sync + 'static, I: Info + 'static> LoaderState<'static, O, I> {
+    pub(crate) fn load_plugin(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Result<(), LoadPluginError> {
         let index = self
             .plugins
             .iter()
@@ -409,7 +1114,11 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
         private_loader::load_plugin(self, index)
     }
 
-    pub fn par_load_plugin(&mut self, id: &str, version: &Version) -> Result<(), LoadPluginError> {
+    pub(crate) fn par_load_plugin(
+        &mut self,
+        id: &str,
+        version: &Version,
+    ) -> Result<(), LoadPluginError> {
         let index = self
             .plugins
             .par_iter()
@@ -418,7 +1127,7 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
         private_loader::load_plugin(self, index)
     }
 
-    pub fn load_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), LoadPluginError> {
+    pub(crate) fn load_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), LoadPluginError> {
         let index = self
             .plugins
             .iter()
@@ -427,7 +1136,10 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
         private_loader::load_plugin(self, index)
     }
 
-    pub fn par_load_plugin_by_bundle(&mut self, bundle: &Bundle) -> Result<(), LoadPluginError> {
+    pub(crate) fn par_load_plugin_by_bundle(
+        &mut self,
+        bundle: &Bundle,
+    ) -> Result<(), LoadPluginError> {
         let index = self
             .plugins
             .par_iter()
@@ -436,15 +1148,7 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
         private_loader::load_plugin(self, index)
     }
 
-    pub unsafe fn forced_load_plugin(
-        &mut self,
-        index: usize,
-        depends: Vec<(Bundle, bool)>,
-    ) -> Result<(), LoadPluginError> {
-        private_loader::forced_load_plugin(self, index, depends)
-    }
-
-    pub fn load_plugin_now(
+    pub(crate) fn load_plugin_now(
         &mut self,
         path: &str,
     ) -> Result<Bundle, (Option<RegisterPluginError>, Option<LoadPluginError>)> {
@@ -454,55 +1158,22 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
         Ok(bundle)
     }
 
-    pub fn load_plugins<'b, P>(
-        &mut self,
-        paths: P,
-    ) -> Result<Vec<Bundle>, (Option<RegisterPluginError>, Option<LoadPluginError>)>
-    where
-        P: IntoIterator<Item = &'b str>,
-    {
-        let bundles = self.register_plugins(paths).map_err(|e| (Some(e), None))?;
-
-        // Перебор плагинов, которые не являются зависимостями для других плагинов
-        let result: Vec<_> = self
-            .plugins
-            .iter()
-            .enumerate()
-            .filter_map(|(index, plugin)| {
-                let find_plugin = self.plugins.iter().find(|pl| {
-                    pl.info
-                        .info
-                        .depends()
-                        .iter()
-                        .chain(pl.info.info.optional_depends().iter())
-                        .any(|d| {
-                            *d == plugin.info.bundle
-                                && self
-                                    .plugins
-                                    .iter()
-                                    .find(|p| {
-                                        d.version.matches(&p.info.bundle.version)
-                                            && p.info.bundle.version > plugin.info.bundle.version
-                                    })
-                                    .is_none()
-                        })
-                });
-
-                match find_plugin {
-                    Some(_) => None,
-                    None => Some(index),
-                }
-            })
-            .collect();
+    pub(crate) fn load_plugins<'b, P>(
+        &mut self,
+        paths: P,
+    ) -> Result<Vec<Bundle>, (Option<RegisterPluginError>, Option<LoadPluginError>)>
+    where
+        P: IntoIterator<Item = &'b str>,
+    {
+        let bundles = self.register_plugins(paths).map_err(|e| (Some(e), None))?;
 
-        result.into_iter().try_for_each(|index| {
-            private_loader::load_plugin(self, index).map_err(|e| (None, Some(e)))
-        })?;
+        let roots = resolver::roots(&self.plugins);
+        private_loader::load_roots(self, &roots).map_err(|e| (None, Some(e)))?;
 
         Ok(bundles)
     }
 
-    pub fn par_load_plugins<'b, P>(
+    pub(crate) fn par_load_plugins<'b, P>(
         &mut self,
         paths: P,
     ) -> Result<Vec<Bundle>, (Option<RegisterPluginError>, Option<LoadPluginError>)>
@@ -513,47 +1184,14 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
             .par_register_plugins(paths)
             .map_err(|e| (Some(e), None))?;
 
-        // Перебор плагинов, которые не являются зависимостями для других плагинов
-        let result: Vec<_> = self
-            .plugins
-            .par_iter()
-            .enumerate()
-            .filter_map(|(index, plugin)| {
-                let find_plugin = self.plugins.iter().find(|pl| {
-                    pl.info
-                        .info
-                        .depends()
-                        .iter()
-                        .chain(pl.info.info.optional_depends().iter())
-                        .any(|d| {
-                            *d == plugin.info.bundle
-                                && self
-                                    .plugins
-                                    .iter()
-                                    .find(|p| {
-                                        d.version.matches(&p.info.bundle.version)
-                                            && p.info.bundle.version > plugin.info.bundle.version
-                                    })
-                                    .is_none()
-                        })
-                });
-
-                match find_plugin {
-                    Some(_) => None,
-                    None => Some(index),
-                }
-            })
-            .collect();
-
-        let this = Ptr::new(self);
-        result.into_par_iter().try_for_each(move |index| {
-            private_loader::load_plugin(this.as_mut(), index).map_err(|e| (None, Some(e)))
-        })?;
+        // Загрузка требует монопольного доступа к менеджерам
+        let roots = resolver::roots(&self.plugins);
+        private_loader::load_roots(self, &roots).map_err(|e| (None, Some(e)))?;
 
         Ok(bundles)
     }
 
-    pub fn load_only_used_plugins<'b, P>(
+    pub(crate) fn load_only_used_plugins<'b, P>(
         &mut self,
         paths: P,
     ) -> Result<
@@ -571,75 +1209,15 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
             .register_plugins(paths)
             .map_err(|e| (Some(e), None, None))?;
 
-        // Перебор плагинов, которые не являются зависимостями для других плагинов
-        let (used, unused): (Vec<_>, Vec<_>) = self
-            .plugins
-            .iter()
-            .enumerate()
-            .filter_map(|(index, plugin)| {
-                let find_plugin = self.plugins.iter().find(|pl| {
-                    pl.info
-                        .info
-                        .depends()
-                        .iter()
-                        .chain(pl.info.info.optional_depends().iter())
-                        .any(|d| {
-                            *d == plugin.info.bundle
-                                && self
-                                    .plugins
-                                    .iter()
-                                    .find(|p| {
-                                        d.version.matches(&p.info.bundle.version)
-                                            && p.info.bundle.version > plugin.info.bundle.version
-                                    })
-                                    .is_none()
-                        })
-                });
-
-                match find_plugin {
-                    Some(_) => None,
-                    None => Some(index),
-                }
-            })
-            .partition(|index| {
-                let bundle = &self.plugins[*index].info.bundle;
-
-                // Ищем самую высокую версию
-                self.plugins
-                    .iter()
-                    .find(|pl| {
-                        pl.info.bundle.id == bundle.id && pl.info.bundle.version > bundle.version
-                    })
-                    .is_none()
-            });
-
-        used.into_iter().try_for_each(|index| {
-            private_loader::load_plugin(self, index).map_err(|e| (None, None, Some(e)))
-        })?;
-
-        let mut old_indexs = vec![];
-        let mut unused = unused.into_iter();
-
-        while let Some(index) = unused.next() {
-            let swap = old_indexs
-                .iter()
-                .fold(0, |acc, i| if index > *i { acc + 1 } else { acc });
-
-            let new_index = index - swap;
-
-            let bundle = &self.plugins[new_index].info.bundle;
-            bundles.retain(|b| *b != *bundle);
-
-            private_loader::unregister_plugin(&mut self.plugins, new_index)
-                .map_err(|e| (None, Some(e), None))?;
-
-            old_indexs.push(index);
-        }
+        let roots = private_loader::latest_roots(&self.plugins);
+        private_loader::load_roots(self, &roots).map_err(|e| (None, None, Some(e)))?;
+        private_loader::unregister_unloaded(self, &mut bundles)
+            .map_err(|e| (None, Some(e), None))?;
 
         Ok(bundles)
     }
 
-    pub fn par_load_only_used_plugins<'b, P>(
+    pub(crate) fn par_load_only_used_plugins<'b, P>(
         &mut self,
         paths: P,
     ) -> Result<
@@ -653,95 +1231,37 @@ impl<O: Send + Sync + 'static, I: Info + 'static> Loader<'static, O, I> {
     where
         P: IntoParallelIterator<Item = &'b str>,
     {
-        let bundles = self
+        let mut bundles = self
             .par_register_plugins(paths)
             .map_err(|e| (Some(e), None, None))?;
 
-        // Перебор плагинов, которые не являются зависимостями для других плагинов
-        let (used, unused): (Vec<_>, Vec<_>) = self
-            .plugins
-            .iter()
-            .enumerate()
-            .filter_map(|(index, plugin)| {
-                let find_plugin = self.plugins.iter().find(|pl| {
-                    pl.info
-                        .info
-                        .depends()
-                        .iter()
-                        .chain(pl.info.info.optional_depends().iter())
-                        .any(|d| {
-                            *d == plugin.info.bundle
-                                && self
-                                    .plugins
-                                    .iter()
-                                    .find(|p| {
-                                        d.version.matches(&p.info.bundle.version)
-                                            && p.info.bundle.version > plugin.info.bundle.version
-                                    })
-                                    .is_none()
-                        })
-                });
-
-                match find_plugin {
-                    Some(_) => None,
-                    None => Some(index),
-                }
-            })
-            .partition(|index| {
-                let bundle = &self.plugins[*index].info.bundle;
-
-                // Ищем самую высокую версию
-                self.plugins
-                    .iter()
-                    .find(|pl| {
-                        pl.info.bundle.id == bundle.id && pl.info.bundle.version > bundle.version
-                    })
-                    .is_none()
-            });
-
-        let this = Ptr::new(self);
-        used.into_iter().try_for_each(|index| {
-            private_loader::load_plugin(this.as_mut(), index).map_err(|e| (None, None, Some(e)))
-        })?;
-
-        let mut old_indexs = vec![];
-        let mut unused = unused.into_iter();
-
-        while let Some(index) = unused.next() {
-            let swap = old_indexs
-                .iter()
-                .fold(0, |acc, i| if index > *i { acc + 1 } else { acc });
-
-            private_loader::unregister_plugin(&mut this.as_mut().plugins, index - swap)
-                .map_err(|e| (None, Some(e), None))?;
-
-            old_indexs.push(index);
-        }
+        let roots = private_loader::latest_roots(&self.plugins);
+        private_loader::load_roots(self, &roots).map_err(|e| (None, None, Some(e)))?;
+        private_loader::unregister_unloaded(self, &mut bundles)
+            .map_err(|e| (None, Some(e), None))?;
 
         Ok(bundles)
     }
 }
 
-impl<O: Send + Sync, I: Info> Drop for Loader<'_, O, I> {
-    fn drop(&mut self) {
-        self.stop().unwrap();
-    }
-}
-
 mod private_loader {
-    use std::path::Path;
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    };
 
     use crate::{
+        resolver::{self, Resolution},
         utils::{
-            LoadPluginError, Ptr, RegisterManagerError, RegisterPluginError, StopLoaderError,
-            UnloadPluginError, UnregisterManagerError, UnregisterPluginError,
+            LoadPluginError, RegisterManagerError, RegisterPluginError, ReloadPluginError,
+            StopLoaderError, UnloadPluginError, UnregisterManagerError, UnregisterPluginError,
         },
-        Api, Bundle, Depend, Info, LoadPluginContext, Manager, Plugin, PluginInfo,
+        Api, Bundle, Events, Info, LoadPluginContext, LoaderEvent, Manager, Plugin, PluginInfo,
         RegisterPluginContext,
     };
 
     pub fn stop_plugins<O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'_, O, I>,
+        loader: &mut super::LoaderState<'_, O, I>,
     ) -> Result<(), StopLoaderError> {
         // Сортируем плагины в порядке их зависимостей
         let sort_plugins = sort_plugins(
@@ -758,7 +1278,7 @@ mod private_loader {
         let errors = sort_plugins
             .iter()
             .map(|index| {
-                forced_unload_plugin(&mut loader.plugins, index.clone())
+                forced_unload_plugin(loader, index.clone())
                     .map_err(|e| UnregisterPluginError::UnloadError(e))
             })
             .partition::<Vec<_>, _>(|r| r.is_err())
@@ -772,7 +1292,7 @@ mod private_loader {
 
         //TODO: Добавить debug вывод
         let errors = (0..loader.plugins.len())
-            .map(|_| forced_unregister_plugin(&mut loader.plugins, 0_usize))
+            .map(|_| forced_unregister_plugin(loader, 0_usize))
             .partition::<Vec<_>, _>(|r| r.is_err())
             .0;
 
@@ -785,12 +1305,13 @@ mod private_loader {
     }
 
     pub fn stop_managers<'a, O: Send + Sync, I: Info>(
-        loader: &'a mut super::Loader<'_, O, I>,
+        loader: &'a mut super::LoaderState<'_, O, I>,
     ) -> Result<(), StopLoaderError> {
         // Открепляем менеджеры плагинов от загрузчика
         let mut errors = vec![];
         while !loader.managers.is_empty() {
-            if let Err(e) = forced_unregister_manager(&mut loader.managers, 0_usize) {
+            if let Err(e) = forced_unregister_manager(&mut loader.managers, &loader.events, 0_usize)
+            {
                 errors.push(e);
             }
         }
@@ -919,16 +1440,19 @@ mod private_loader {
     }
 
     pub fn forced_register_manager<'a, O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'a, O, I>,
+        loader: &mut super::LoaderState<'a, O, I>,
         mut manager: Box<dyn Manager<'a, O, I>>,
     ) -> Result<(), RegisterManagerError> {
         manager.as_mut().register_manager()?;
+        loader.events.emit(LoaderEvent::ManagerRegistered {
+            format: manager.format(),
+        });
         loader.managers.push(manager);
         Ok(())
     }
 
     pub fn register_manager<'a, O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'a, O, I>,
+        loader: &mut super::LoaderState<'a, O, I>,
         manager: Box<dyn Manager<'a, O, I>>,
     ) -> Result<(), RegisterManagerError> {
         if let Some(_) = loader.managers.iter().find(|m| manager == **m) {
@@ -942,31 +1466,35 @@ mod private_loader {
 
     pub fn forced_unregister_manager<O: Send + Sync, I: Info>(
         managers: &mut Vec<Box<dyn Manager<'_, O, I>>>,
+        events: &Events,
         index: usize,
     ) -> Result<(), UnregisterManagerError> {
-        match managers.remove(index).unregister_manager() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(UnregisterManagerError::UnregisterManagerByManager(e)),
-        }
+        let mut manager = managers.remove(index);
+        manager
+            .unregister_manager()
+            .map_err(UnregisterManagerError::UnregisterManagerByManager)?;
+
+        events.emit(LoaderEvent::ManagerUnregistered {
+            format: manager.format(),
+        });
+        Ok(())
     }
 
     pub fn unregister_manager<O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'_, O, I>,
+        loader: &mut super::LoaderState<'_, O, I>,
         index: usize,
     ) -> Result<(), UnregisterManagerError> {
-        let manager = &loader.managers[index];
+        let format = loader.managers[index].format();
 
         // Получаем все плагины, относящиеся к менеджеру
         let plugins_from_manager = loader
             .plugins
             .iter()
             .enumerate()
-            .filter_map(
-                |(index, plugin)| match *plugin.manager.as_ref() == *manager {
-                    true => Some(index),
-                    false => None,
-                },
-            )
+            .filter_map(|(index, plugin)| match plugin.info.bundle.format == format {
+                true => Some(index),
+                false => None,
+            })
             .collect();
 
         // Сортируем плагины менеджера в порядке их зависимостей
@@ -974,7 +1502,7 @@ mod private_loader {
 
         // Выгружаем плагины
         for index in sort_plugins.iter() {
-            unload_plugin(&mut loader.plugins, index.clone()).map_err(|e| {
+            unload_plugin(loader, index.clone()).map_err(|e| {
                 UnregisterManagerError::UnregisterPlugin(UnregisterPluginError::UnloadError(e))
             })?;
         }
@@ -987,44 +1515,157 @@ mod private_loader {
                 .iter()
                 .fold(0, |acc, i| if index > *i { acc + 1 } else { acc });
 
-            forced_unregister_plugin(&mut loader.plugins, index - swap)
+            forced_unregister_plugin(loader, index - swap)
                 .map_err(|e| UnregisterManagerError::UnregisterPlugin(e))?;
 
             old_indexs.push(index);
         }
 
         // Выгружаем менеджер
-        forced_unregister_manager(&mut loader.managers, index)
+        forced_unregister_manager(&mut loader.managers, &loader.events, index)
     }
 
-    pub fn forced_register_plugin<'a, O: Send + Sync, I: Info>(
-        plugins: &mut Vec<Plugin<'a, O, I>>,
-        manager: Ptr<'a, Box<dyn Manager<'a, O, I>>>,
+    // Менеджер плагина определяется форматом его бандла
+    fn manager_mut<'m, 'a, O: Send + Sync, I: Info>(
+        managers: &'m mut [Box<dyn Manager<'a, O, I>>],
+        format: &str,
+    ) -> Option<&'m mut Box<dyn Manager<'a, O, I>>> {
+        managers.iter_mut().find(|manager| manager.format() == format)
+    }
+
+    pub fn forced_register_plugin<O: Send + Sync, I: Info>(
+        loader: &mut super::LoaderState<'_, O, I>,
         plugin_info: PluginInfo<I>,
     ) -> Result<Bundle, RegisterPluginError> {
         let bundle = plugin_info.bundle.clone();
-        plugins.push(Plugin::<'a>::new(manager, plugin_info));
+        loader.plugins.push(Plugin::new(plugin_info));
+        loader
+            .events
+            .emit(LoaderEvent::PluginRegistered { bundle: &bundle });
         Ok(bundle)
     }
 
+    // Бандл, проверенный и распакованный, но ещё не зарегистрированный менеджером
+    pub struct PreparedPlugin {
+        path: PathBuf,
+        bundle: Bundle,
+        cache: Option<PathBuf>,
+    }
+
+    impl PreparedPlugin {
+        pub fn remove_cache(self) {
+            if let Some(cache) = self.cache {
+                let _ = std::fs::remove_dir_all(cache);
+            }
+        }
+    }
+
     pub fn register_plugin<'a, O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'a, O, I>,
+        loader: &mut super::LoaderState<'a, O, I>,
         path: &str,
     ) -> Result<Bundle, RegisterPluginError> {
+        let plugin = prepare_plugin(loader, path)?;
+        register_prepared_plugin(loader, plugin)
+    }
+
+    pub fn prepare_plugin<'a, O: Send + Sync, I: Info>(
+        loader: &super::LoaderState<'a, O, I>,
+        path: &str,
+    ) -> Result<PreparedPlugin, RegisterPluginError> {
         let path = Path::new(path).to_path_buf();
 
-        if !path.is_dir() {
+        #[cfg(feature = "archive")]
+        let is_exists = path.exists();
+        #[cfg(not(feature = "archive"))]
+        let is_exists = path.is_dir();
+
+        if !is_exists {
             return Err(RegisterPluginError::NotFound);
         }
 
-        if let None = path.extension() {
-            return Err(RegisterPluginError::UnknownManagerFormat("".to_string()));
+        // Распаковываем бандл из архива
+        #[cfg(feature = "archive")]
+        let (path, cache) = match path.is_file() {
+            true => {
+                let cache = crate::utils::archive::unzip_to_cache(&path)?;
+                (cache.join(path.file_name().unwrap()), Some(cache))
+            }
+            false => (path, None),
+        };
+        #[cfg(not(feature = "archive"))]
+        let cache: Option<PathBuf> = None;
+
+        // Бандл определяется по распакованному каталогу
+        let bundle = match name_plugin(loader, &path) {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                if let Some(cache) = cache {
+                    let _ = std::fs::remove_dir_all(cache);
+                }
+                return Err(e);
+            }
+        };
+
+        Ok(PreparedPlugin {
+            path,
+            bundle,
+            cache,
+        })
+    }
+
+    fn name_plugin<'a, O: Send + Sync, I: Info>(
+        loader: &super::LoaderState<'a, O, I>,
+        path: &Path,
+    ) -> Result<Bundle, RegisterPluginError> {
+        let bundle = loader.naming.bundle(path)?;
+        check_plugin(loader, &bundle)?;
+        #[cfg(feature = "integrity")]
+        verify_integrity(loader, path, &bundle)?;
+        Ok(bundle)
+    }
+
+    // Подпись проверяется всегда, если задано хранилище ключей.
+    // При Warn расхождения только сообщаются подписчикам загрузчика.
+    #[cfg(feature = "integrity")]
+    fn verify_integrity<'a, O: Send + Sync, I: Info>(
+        loader: &super::LoaderState<'a, O, I>,
+        path: &Path,
+        bundle: &Bundle,
+    ) -> Result<(), crate::utils::IntegrityError> {
+        use crate::utils::{IntegrityError, IntegrityPolicy, Manifest};
+
+        #[cfg(feature = "signing")]
+        if let Some(trust_store) = &loader.trust_store {
+            return crate::utils::signing::verify_bundle(path, trust_store);
+        }
+
+        if loader.integrity == IntegrityPolicy::Off {
+            return Ok(());
         }
 
-        let bundle = Bundle::from_filename(path.file_name().unwrap())?;
+        let result = Manifest::read(path).and_then(|manifest| match manifest {
+            Some(manifest) => manifest.verify(path),
+            None => Err(IntegrityError::MissingManifest),
+        });
 
+        match (result, loader.integrity) {
+            (Err(error), IntegrityPolicy::Warn) => {
+                loader.events.emit(LoaderEvent::IntegrityWarning {
+                    bundle,
+                    error: &error,
+                });
+                Ok(())
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn check_plugin<'a, O: Send + Sync, I: Info>(
+        loader: &super::LoaderState<'a, O, I>,
+        bundle: &Bundle,
+    ) -> Result<(), RegisterPluginError> {
         // Проверяем, есть ли уже такой плагин
-        if loader.get_plugin_by_bundle(&bundle).is_some() {
+        if loader.get_plugin_by_bundle(bundle).is_some() {
             return Err(RegisterPluginError::AlreadyExistsIDAndVersion(
                 bundle.id.clone(),
                 bundle.version.clone(),
@@ -1032,159 +1673,297 @@ mod private_loader {
         }
 
         // Ищем подходящий менеджер
-        let plugin_format = bundle.format.clone();
-        let manager = loader
-            .get_manager_mut(plugin_format.as_str())
-            .ok_or(RegisterPluginError::UnknownManagerFormat(plugin_format))?;
+        if loader.get_manager_ref(bundle.format.as_str()).is_none() {
+            return Err(RegisterPluginError::UnknownManagerFormat(
+                bundle.format.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn register_prepared_plugin<'a, O: Send + Sync, I: Info>(
+        loader: &mut super::LoaderState<'a, O, I>,
+        plugin: PreparedPlugin,
+    ) -> Result<Bundle, RegisterPluginError> {
+        // Состояние могло измениться после подготовки бандла
+        if let Err(e) = check_plugin(loader, &plugin.bundle) {
+            plugin.remove_cache();
+            return Err(e);
+        }
+
+        let PreparedPlugin {
+            path,
+            bundle,
+            cache,
+        } = plugin;
 
         // Менеджер регистрирует плагин
-        let info = manager.register_plugin(RegisterPluginContext {
+        let manager = loader.get_manager_mut(bundle.format.as_str()).unwrap();
+        let info = match manager.register_plugin(RegisterPluginContext {
             path: &path,
             bundle: &bundle,
-        })?;
+        }) {
+            Ok(info) => info,
+            Err(e) => {
+                if let Some(cache) = cache {
+                    let _ = std::fs::remove_dir_all(cache);
+                }
+                return Err(e.into());
+            }
+        };
         let plugin_info = PluginInfo { path, bundle, info };
 
         // Регистрируем плагин
-        let manager = Ptr::<'a>::new(manager);
-        forced_register_plugin(&mut loader.plugins, manager, plugin_info)
+        let bundle = forced_register_plugin(loader, plugin_info)?;
+        loader.plugins.last_mut().unwrap().cache = cache;
+
+        Ok(bundle)
     }
 
     pub fn forced_unregister_plugin<O: Send + Sync, I: Info>(
-        plugins: &mut Vec<Plugin<'_, O, I>>,
+        loader: &mut super::LoaderState<'_, O, I>,
         index: usize,
     ) -> Result<(), UnregisterPluginError> {
-        let plugin = plugins.remove(index);
-        plugin.manager.as_mut().unregister_plugin(&plugin)?;
-        Ok(())
+        let plugin = loader.plugins.remove(index);
+        let result = match manager_mut(&mut loader.managers, &plugin.info.bundle.format) {
+            Some(manager) => manager.unregister_plugin(&plugin).map_err(Into::into),
+            None => Err(UnregisterPluginError::HasUnregisteredManager),
+        };
+
+        if result.is_ok() {
+            loader.events.emit(LoaderEvent::PluginUnregistered {
+                bundle: &plugin.info.bundle,
+            });
+        }
+
+        // Плагин уже удалён из загрузчика, поэтому распакованный бандл
+        // удаляется в любом случае, а ошибка удаления не важна
+        if let Some(cache) = &plugin.cache {
+            let _ = std::fs::remove_dir_all(cache);
+        }
+
+        result
     }
 
-    pub fn unregister_plugin<'a, O: Send + Sync, I: Info>(
-        plugins: &mut Vec<Plugin<'_, O, I>>,
+    pub fn unregister_plugin<O: Send + Sync, I: Info>(
+        loader: &mut super::LoaderState<'_, O, I>,
         index: usize,
     ) -> Result<(), UnregisterPluginError> {
-        unload_plugin(plugins, index)?;
-        forced_unregister_plugin(plugins, index)
+        unload_plugin(loader, index)?;
+        forced_unregister_plugin(loader, index)
     }
 
-    pub fn forced_load_plugin<O: Send + Sync, I: Info>(
-        loader: *mut super::Loader<'static, O, I>,
+    pub fn forced_load_plugin<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
         index: usize,
         depends: Vec<(Bundle, bool)>,
     ) -> Result<(), LoadPluginError> {
-        let manager = Ptr::new(unsafe { &*loader }.plugins[index].manager.as_ptr());
+        let handle = loader.handle.upgrade().unwrap();
 
-        // Получаем плагин и его зависимости
-        let plugin = &mut unsafe { &mut *loader }.plugins[index];
-
-        // Делим зависимости
+        // Делим зависимости. Их функции передаются в Api заранее,
+        // так как во время загрузки плагина загрузчик заблокирован.
         let mut deps = vec![];
         let mut opt_deps = vec![];
+        let mut functions = HashMap::new();
 
         for (bundle, is_depend) in depends {
+            if let Some(plugin) = loader.get_plugin_by_bundle(&bundle) {
+                functions.insert(bundle.clone(), plugin.registry.clone());
+            }
+
             match is_depend {
                 true => deps.push(bundle),
                 false => opt_deps.push(bundle),
             }
         }
 
+        let requests = handle
+            .requests
+            .read()
+            .ok_or(LoadPluginError::LoaderLocked)?;
+
+        // Получаем плагин и его менеджер
+        let plugin = &mut loader.plugins[index];
+        let manager = manager_mut(&mut loader.managers, &plugin.info.bundle.format)
+            .ok_or(LoadPluginError::HasUnregisteredManager)?;
+
         // Загружаем плагин
         let bundle = plugin.info.bundle.clone();
+        let requests_len = plugin.requests.len();
 
-        manager.as_mut().load_plugin(
-            LoadPluginContext::new(plugin, &unsafe { &*loader }.requests),
-            Api::new(Ptr::new(loader), bundle, deps, opt_deps),
+        manager.load_plugin(
+            LoadPluginContext::new(plugin, &requests),
+            Api::new(handle.clone(), bundle, deps, opt_deps, functions),
         )?;
 
         plugin.is_load = true;
 
+        // Сообщаем о зарегистрированных запросах
+        for request in plugin.requests[requests_len..].iter() {
+            loader.events.emit(LoaderEvent::RequestRegistered {
+                bundle: &plugin.info.bundle,
+                name: &request.name(),
+            });
+        }
+
         Ok(())
     }
 
-    fn load_depends<'a, O, I, IT>(
-        loader: &'a mut super::Loader<'static, O, I>,
-        depends_iter: IT,
-    ) -> Result<(Vec<(Bundle, bool)>, Vec<Depend>), LoadPluginError>
-    where
-        O: Send + Sync,
-        I: Info,
-        IT: IntoIterator<Item = (bool, Depend)>,
-    {
+    pub fn emit_load_result(
+        events: &Events,
+        bundle: &Bundle,
+        result: Result<(), LoadPluginError>,
+    ) -> Result<(), LoadPluginError> {
+        match &result {
+            Ok(_) => events.emit(LoaderEvent::PluginLoaded { bundle }),
+            Err(error) => events.emit(LoaderEvent::PluginLoadFailed { bundle, error }),
+        }
+        result
+    }
+
+    fn load_depends<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
+        index: usize,
+        resolution: &Resolution,
+    ) -> Result<Vec<(Bundle, bool)>, LoadPluginError> {
         let mut found_depends = vec![];
-        let mut not_found_depends = vec![];
 
-        for (is_depend, depend) in depends_iter.into_iter() {
-            if let Some((index, plugin)) = loader.plugins.iter().enumerate().find(|(_, plugin)| {
-                depend == plugin.info.bundle
-                    && loader
-                        .plugins
-                        .iter()
-                        .find(|p| {
-                            depend.version.matches(&p.info.bundle.version)
-                                && p.info.bundle.version > plugin.info.bundle.version
-                        })
-                        .is_none()
-            }) {
-                found_depends.push((plugin.info.bundle.clone(), is_depend));
-                load_plugin(loader, index).map_err(|e| LoadPluginError::LoadDependency {
-                    depend: depend,
+        for (index, depend, is_depend) in resolution.depends(&loader.plugins, index) {
+            found_depends.push((loader.plugins[index].info.bundle.clone(), is_depend));
+            load_resolved_plugin(loader, index, resolution).map_err(|e| {
+                LoadPluginError::LoadDependency {
+                    depend,
                     error: Box::new(e),
-                })?;
-            } else if is_depend {
-                not_found_depends.push(depend);
-            }
+                }
+            })?;
         }
-        Ok((found_depends, not_found_depends))
+
+        Ok(found_depends)
     }
 
-    fn check_requests<O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'static, O, I>,
+    fn check_requests<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
         index: usize,
-    ) -> Vec<String> {
-        let mut plugin_requests = loader.plugins[index].requests.iter();
-        loader
+    ) -> Result<Vec<String>, LoadPluginError> {
+        let handle = loader.handle.upgrade().unwrap();
+        let requests = handle
             .requests
+            .read()
+            .ok_or(LoadPluginError::LoaderLocked)?;
+
+        let mut plugin_requests = loader.plugins[index].requests.iter();
+        Ok(requests
             .iter()
             .filter_map(|req| match plugin_requests.any(|r| r.name() == req.name) {
                 true => None,
                 false => Some(req.name.clone()),
             })
-            .collect()
+            .collect())
     }
 
-    pub fn load_plugin<O: Send + Sync, I: Info>(
-        loader: &mut super::Loader<'static, O, I>,
+    pub fn load_plugin<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
         index: usize,
     ) -> Result<(), LoadPluginError> {
         if loader.plugins[index].is_load {
             return Ok(());
         }
 
-        // Загружаем зависимости
-        let info = &loader.plugins[index].info;
-        let depends_iter = info
-            .info
-            .depends()
-            .clone()
+        let bundle = loader.plugins[index].info.bundle.clone();
+        loader
+            .events
+            .emit(LoaderEvent::PluginLoading { bundle: &bundle });
+
+        // Подбираем согласованные версии зависимостей
+        let result = resolver::resolve(&loader.plugins, &[index])
+            .and_then(|resolution| try_load_plugin(loader, index, &resolution));
+        emit_load_result(&loader.events, &bundle, result)
+    }
+
+    // Корни разрешаются вместе, чтобы общие зависимости получили одну версию.
+    // При конфликте не загружается ни один корень.
+    pub fn load_roots<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
+        roots: &[usize],
+    ) -> Result<(), LoadPluginError> {
+        let resolution = resolver::resolve(&loader.plugins, roots)?;
+        roots
+            .iter()
+            .try_for_each(|index| load_resolved_plugin(loader, *index, &resolution))
+    }
+
+    // Из нескольких версий корня используется только самая новая
+    pub fn latest_roots<O: Send + Sync, I: Info>(plugins: &[Plugin<'_, O, I>]) -> Vec<usize> {
+        resolver::roots(plugins)
             .into_iter()
-            .map(|d| (true, d))
-            .chain(
-                info.info
-                    .optional_depends()
-                    .clone()
-                    .into_iter()
-                    .map(|d| (false, d)),
-            );
-        let (found_depends, not_found_depends) = load_depends(loader, depends_iter)?;
-
-        if !not_found_depends.is_empty() {
-            return Err(LoadPluginError::NotFoundDependencies(not_found_depends));
+            .filter(|index| {
+                let bundle = &plugins[*index].info.bundle;
+                !plugins.iter().any(|plugin| {
+                    plugin.info.bundle.id == bundle.id
+                        && plugin.info.bundle.version > bundle.version
+                })
+            })
+            .collect()
+    }
+
+    // Удаляет новые бандлы, которые не понадобились при загрузке
+    pub fn unregister_unloaded<O: Send + Sync, I: Info>(
+        loader: &mut super::LoaderState<'_, O, I>,
+        bundles: &mut Vec<Bundle>,
+    ) -> Result<(), UnregisterPluginError> {
+        let unused: Vec<_> = bundles
+            .iter()
+            .filter(|bundle| {
+                loader
+                    .plugins
+                    .iter()
+                    .any(|plugin| plugin == *bundle && !plugin.is_load)
+            })
+            .cloned()
+            .collect();
+
+        for bundle in unused {
+            if let Some(index) = loader.plugins.iter().position(|plugin| *plugin == bundle) {
+                unregister_plugin(loader, index)?;
+            }
+            bundles.retain(|b| *b != bundle);
+        }
+
+        Ok(())
+    }
+
+    fn load_resolved_plugin<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
+        index: usize,
+        resolution: &Resolution,
+    ) -> Result<(), LoadPluginError> {
+        if loader.plugins[index].is_load {
+            return Ok(());
         }
 
+        let bundle = loader.plugins[index].info.bundle.clone();
+        loader
+            .events
+            .emit(LoaderEvent::PluginLoading { bundle: &bundle });
+
+        let result = try_load_plugin(loader, index, resolution);
+        emit_load_result(&loader.events, &bundle, result)
+    }
+
+    fn try_load_plugin<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
+        index: usize,
+        resolution: &Resolution,
+    ) -> Result<(), LoadPluginError> {
+        // Загружаем зависимости
+        let found_depends = load_depends(loader, index, resolution)?;
+
         // Загружаем плагин
         forced_load_plugin(loader, index, found_depends)?;
 
         // Проверяем наличие запрашиваемых функций
-        let not_found_requests = check_requests(loader, index);
+        let not_found_requests = check_requests(loader, index)?;
 
         if !not_found_requests.is_empty() {
             loader.plugins[index].is_load = false;
@@ -1195,25 +1974,34 @@ mod private_loader {
     }
 
     pub fn forced_unload_plugin<O: Send + Sync, I: Info>(
-        plugins: &mut Vec<Plugin<'_, O, I>>,
+        loader: &mut super::LoaderState<'_, O, I>,
         index: usize,
     ) -> Result<(), UnloadPluginError> {
-        if plugins[index].is_load {
-            plugins[index]
-                .manager
-                .as_mut()
-                .unload_plugin(&plugins[index])?;
+        let plugin = &mut loader.plugins[index];
+        if plugin.is_load {
+            manager_mut(&mut loader.managers, &plugin.info.bundle.format)
+                .ok_or(UnloadPluginError::HasUnregisteredManager)?
+                .unload_plugin(plugin)?;
+
+            // Функции выгруженного плагина больше недействительны
+            plugin.requests.clear();
+            plugin.registry.clear();
+
+            loader.events.emit(LoaderEvent::PluginUnloaded {
+                bundle: &plugin.info.bundle,
+            });
         }
 
-        plugins[index].is_load = false;
+        plugin.is_load = false;
 
         Ok(())
     }
 
-    pub fn unload_plugin<'a, O: Send + Sync, I: Info>(
-        plugins: &mut Vec<Plugin<'_, O, I>>,
+    pub fn unload_plugin<O: Send + Sync, I: Info>(
+        loader: &mut super::LoaderState<'_, O, I>,
         index: usize,
     ) -> Result<(), UnloadPluginError> {
+        let plugins = &loader.plugins;
         if plugins[index].is_load {
             let bundle = &plugins[index].info.bundle;
 
@@ -1247,6 +2035,97 @@ mod private_loader {
             })?;
         }
 
-        forced_unload_plugin(plugins, index)
+        forced_unload_plugin(loader, index)
+    }
+
+    // Загруженные плагины, которые прямо или косвенно зависят от плагина
+    fn dependents<O: Send + Sync, I: Info>(
+        plugins: &[Plugin<'_, O, I>],
+        index: usize,
+    ) -> Vec<usize> {
+        let mut result = vec![index];
+
+        let mut i = 0;
+        while i < result.len() {
+            let bundle = &plugins[result[i]].info.bundle;
+
+            for (index, plugin) in plugins.iter().enumerate() {
+                let is_depend = plugin
+                    .info
+                    .info
+                    .depends()
+                    .iter()
+                    .chain(plugin.info.info.optional_depends().iter())
+                    .any(|depend| {
+                        *depend == *bundle
+                            && !plugins.iter().any(|p| {
+                                depend.version.matches(&p.info.bundle.version)
+                                    && p.info.bundle.version > bundle.version
+                            })
+                    });
+
+                if plugin.is_load && is_depend && !result.contains(&index) {
+                    result.push(index);
+                }
+            }
+
+            i += 1;
+        }
+
+        result
+    }
+
+    pub fn reload_plugin<O: Send + Sync + 'static, I: Info + 'static>(
+        loader: &mut super::LoaderState<'static, O, I>,
+        index: usize,
+        snapshot: Option<&Path>,
+    ) -> Result<(), ReloadPluginError> {
+        if !loader.plugins[index].is_load {
+            return Ok(());
+        }
+
+        // Выгружаем сначала зависимые плагины, затем сам плагин
+        let order = sort_plugins(&loader.plugins, dependents(&loader.plugins, index));
+
+        for (i, plugin) in order.iter().enumerate() {
+            if let Err(e) = forced_unload_plugin(loader, *plugin) {
+                order[..i].iter().rev().for_each(|index| {
+                    let _ = load_plugin(loader, *index);
+                });
+                return Err(e.into());
+            }
+        }
+
+        // Загружаем в обратном порядке
+        let error = match order.iter().rev().try_for_each(|i| load_plugin(loader, *i)) {
+            Ok(_) => return Ok(()),
+            Err(e) => e,
+        };
+
+        // Без сохранённой копии (ручная перезагрузка без наблюдателя) откатиться
+        // нельзя: загружаем всё, что удаётся, чтобы неизменённые плагины продолжили работать
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot.to_path_buf(),
+            None => {
+                order.iter().rev().for_each(|index| {
+                    let _ = load_plugin(loader, *index);
+                });
+                return Err(error.into());
+            }
+        };
+
+        // Откатываемся к сохранённой копии плагина
+        order.iter().for_each(|index| {
+            let _ = forced_unload_plugin(loader, *index);
+        });
+
+        let path = std::mem::replace(&mut loader.plugins[index].info.path, snapshot);
+        let rollback = order.iter().rev().try_for_each(|i| load_plugin(loader, *i));
+        loader.plugins[index].info.path = path;
+
+        match rollback {
+            Ok(_) => Err(error.into()),
+            Err(rollback) => Err(ReloadPluginError::RollbackFailed { error, rollback }),
+        }
     }
 }
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use crate::{
+    utils::{
+        integrity::{Manifest, MANIFEST_FILE},
+        BundleFromError, IntegrityError,
+    },
+    Bundle,
+};
+
+// Способ определить бандл по пути к плагину
+pub trait BundleNaming: Send + Sync {
+    fn bundle(&self, path: &Path) -> Result<Bundle, BundleFromError>;
+}
+
+// Имя каталога вида `<id>-v<version>.<format>`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FilenameNaming;
+
+impl BundleNaming for FilenameNaming {
+    fn bundle(&self, path: &Path) -> Result<Bundle, BundleFromError> {
+        Bundle::from_filename(path.file_name().ok_or(BundleFromError::NoName)?)
+    }
+}
+
+// ID, версия и формат берутся из манифеста, имя каталога может быть любым
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ManifestNaming;
+
+impl BundleNaming for ManifestNaming {
+    fn bundle(&self, path: &Path) -> Result<Bundle, BundleFromError> {
+        let manifest_path = path.join(MANIFEST_FILE);
+        let failed = |source| BundleFromError::ManifestFailed {
+            path: manifest_path.clone(),
+            source,
+        };
+
+        let bundle = Manifest::read(path)
+            .map_err(failed)?
+            .ok_or_else(|| failed(IntegrityError::MissingManifest))?
+            .bundle
+            .ok_or_else(|| BundleFromError::ManifestWithoutBundle {
+                path: manifest_path.clone(),
+            })?;
+        bundle.check(&manifest_path.to_string_lossy())?;
+
+        Ok(bundle)
+    }
+}
@@ -1,33 +1,34 @@
-use std::{cmp::Ordering, fmt::Debug, sync::Arc};
+use std::{cmp::Ordering, fmt::Debug, marker::PhantomData, path::PathBuf, sync::Arc};
 
 use semver::Version;
 
 use crate::{
     function::Function,
-    utils::{PluginCallFunctionError, PluginCallRequestError, PluginRegisterFunctionError, Ptr},
+    utils::{PluginCallFunctionError, PluginCallRequestError, PluginRegisterFunctionError},
     variable::Variable,
-    Bundle, Depend, Info, Manager, PluginInfo, Registry,
+    Bundle, Depend, Info, PluginInfo, Registry,
 };
 
 pub struct Plugin<'a, O: Send + Sync, I: Info> {
-    pub(crate) manager: Ptr<'a, Box<dyn Manager<'a, O, I>>>,
+    // Менеджер плагина хранится в загрузчике и ищется по формату бандла
+    manager: PhantomData<&'a ()>,
     pub(crate) info: PluginInfo<I>,
     pub(crate) is_load: bool,
     pub(crate) requests: Vec<Box<dyn Function<Output = O>>>,
     pub(crate) registry: Registry<O>,
+    // Каталог, в который был распакован архив бандла
+    pub(crate) cache: Option<PathBuf>,
 }
 
 impl<'a, O: Send + Sync, I: Info> Plugin<'a, O, I> {
-    pub(crate) const fn new(
-        manager: Ptr<'a, Box<dyn Manager<'a, O, I>>>,
-        info: PluginInfo<I>,
-    ) -> Self {
+    pub(crate) const fn new(info: PluginInfo<I>) -> Self {
         Self {
-            manager,
+            manager: PhantomData,
             info,
             is_load: false,
             requests: vec![],
             registry: vec![],
+            cache: None,
         }
     }
 
@@ -0,0 +1,359 @@
+use std::{collections::HashMap, fmt::Display};
+
+use semver::Version;
+
+use crate::{utils::LoadPluginError, Bundle, Depend, Info, Plugin};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyConflict {
+    // Ни одна зарегистрированная версия не подходит
+    Missing {
+        plugin: Bundle,
+        depend: Depend,
+        available: Vec<Version>,
+    },
+    // Уже выбрана другая версия: загруженная, загружаемая или подобранная для другого плагина
+    Incompatible {
+        plugin: Bundle,
+        depend: Depend,
+        selected: Bundle,
+        selected_by: Option<(Bundle, Depend)>,
+    },
+    // Все подходящие версии отвергнуты
+    Rejected {
+        plugin: Bundle,
+        depend: Depend,
+        candidates: Vec<(Bundle, DependencyConflict)>,
+    },
+}
+
+impl DependencyConflict {
+    // Плагин, зависимость которого не удалось разрешить
+    pub fn plugin(&self) -> &Bundle {
+        match self {
+            Self::Missing { plugin, .. }
+            | Self::Incompatible { plugin, .. }
+            | Self::Rejected { plugin, .. } => plugin,
+        }
+    }
+
+    fn fmt_indent(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        match self {
+            Self::Missing {
+                plugin,
+                depend,
+                available,
+            } => match available.is_empty() {
+                true => write!(
+                    f,
+                    "`{plugin}` depends on `{depend}`, but `{}` is not registered",
+                    depend.id
+                ),
+                false => write!(
+                    f,
+                    "`{plugin}` depends on `{depend}`, but only versions {} are registered",
+                    available
+                        .iter()
+                        .map(|version| version.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            Self::Incompatible {
+                plugin,
+                depend,
+                selected,
+                selected_by,
+            } => match selected_by {
+                Some((by, by_depend)) => write!(
+                    f,
+                    "`{plugin}` depends on `{depend}`, but `{selected}` was selected \
+                     because `{by}` depends on `{by_depend}`"
+                ),
+                None => write!(
+                    f,
+                    "`{plugin}` depends on `{depend}`, but `{selected}` is already in use"
+                ),
+            },
+            Self::Rejected {
+                plugin,
+                depend,
+                candidates,
+            } => {
+                write!(
+                    f,
+                    "`{plugin}` depends on `{depend}`, but no matching version can be used:"
+                )?;
+                for (bundle, conflict) in candidates {
+                    write!(f, "\n{:width$}- `{bundle}`: ", "", width = indent * 2 + 2)?;
+                    conflict.fmt_indent(f, indent + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for DependencyConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indent(f, 0)
+    }
+}
+
+#[derive(Clone)]
+struct Requirement {
+    plugin: usize,
+    depend: Depend,
+    required: bool,
+}
+
+// Выбранная версия и зависимость, из-за которой её выбрали
+type Selected = HashMap<String, (usize, Option<(usize, Depend)>)>;
+
+// Согласованный набор версий: по одной версии на каждый ID
+pub(crate) struct Resolution {
+    selected: HashMap<String, usize>,
+}
+
+impl Resolution {
+    // Выбранные зависимости плагина
+    pub(crate) fn depends<O: Send + Sync, I: Info>(
+        &self,
+        plugins: &[Plugin<'_, O, I>],
+        index: usize,
+    ) -> Vec<(usize, Depend, bool)> {
+        let info = &plugins[index].info.info;
+        let depends = info.depends().iter().map(|depend| (depend, true));
+        let optional_depends = info.optional_depends().iter().map(|depend| (depend, false));
+
+        depends
+            .chain(optional_depends)
+            .filter_map(|(depend, is_depend)| {
+                self.selected
+                    .get(&depend.id)
+                    .filter(|index| *depend == plugins[**index].info.bundle)
+                    .map(|index| (*index, depend.clone(), is_depend))
+            })
+            .collect()
+    }
+}
+
+// Плагины, которые не выбираются как зависимость: ни одна зависимость других
+// плагинов не может указать на их ID. Версии остальных плагинов выбирает перебор.
+pub(crate) fn roots<O: Send + Sync, I: Info>(plugins: &[Plugin<'_, O, I>]) -> Vec<usize> {
+    let is_depend = |id: &str| {
+        plugins.iter().any(|plugin| {
+            let info = &plugin.info.info;
+            info.depends()
+                .iter()
+                .chain(info.optional_depends().iter())
+                .any(|depend| depend.id == id && plugins.iter().any(|p| *depend == p.info.bundle))
+        })
+    };
+
+    (0..plugins.len())
+        .filter(|index| !is_depend(&plugins[*index].info.bundle.id))
+        .collect()
+}
+
+// Подбирает одну версию каждой зависимости сразу для всех корней
+pub(crate) fn resolve<O: Send + Sync, I: Info>(
+    plugins: &[Plugin<'_, O, I>],
+    roots: &[usize],
+) -> Result<Resolution, LoadPluginError> {
+    // Отсутствующие прямые зависимости
+    let not_found_depends: Vec<Depend> = roots
+        .iter()
+        .flat_map(|index| plugins[*index].info.info.depends().iter())
+        .filter(|depend| !plugins.iter().any(|plugin| **depend == plugin.info.bundle))
+        .cloned()
+        .collect();
+
+    if !not_found_depends.is_empty() {
+        return Err(LoadPluginError::NotFoundDependencies(not_found_depends));
+    }
+
+    // Загруженные плагины уже выбраны. Если загружено несколько версий
+    // одного ID, выбор между ними остаётся за перебором.
+    let mut selected = Selected::new();
+    let mut ambiguous = vec![];
+    for (index, plugin) in plugins.iter().enumerate().filter(|(_, p)| p.is_load) {
+        let id = &plugin.info.bundle.id;
+        if selected.insert(id.clone(), (index, None)).is_some() {
+            ambiguous.push(id.clone());
+        }
+    }
+    ambiguous.iter().for_each(|id| {
+        selected.remove(id);
+    });
+
+    let mut pending = vec![];
+    for index in roots {
+        selected.insert(plugins[*index].info.bundle.id.clone(), (*index, None));
+        pending.extend(requirements(plugins, *index));
+    }
+
+    solve(plugins, &mut selected, &pending).map_err(LoadPluginError::DependencyConflict)?;
+
+    let resolution = Resolution {
+        selected: selected
+            .into_iter()
+            .map(|(id, (index, _))| (id, index))
+            .collect(),
+    };
+
+    match roots
+        .iter()
+        .find_map(|index| find_cycle(plugins, &resolution, *index))
+    {
+        Some(cycle) => Err(LoadPluginError::DependencyCycle(cycle)),
+        None => Ok(resolution),
+    }
+}
+
+// Поиск в глубину по выбранным зависимостям.
+// Возвращает путь цикла, где первый и последний плагины совпадают.
+fn find_cycle<O: Send + Sync, I: Info>(
+    plugins: &[Plugin<'_, O, I>],
+    resolution: &Resolution,
+    index: usize,
+) -> Option<Vec<Bundle>> {
+    fn visit<O: Send + Sync, I: Info>(
+        plugins: &[Plugin<'_, O, I>],
+        resolution: &Resolution,
+        index: usize,
+        stack: &mut Vec<usize>,
+        visited: &mut Vec<usize>,
+    ) -> Option<Vec<Bundle>> {
+        if let Some(position) = stack.iter().position(|i| *i == index) {
+            return Some(
+                stack[position..]
+                    .iter()
+                    .chain(std::iter::once(&index))
+                    .map(|i| plugins[*i].info.bundle.clone())
+                    .collect(),
+            );
+        }
+
+        if visited.contains(&index) {
+            return None;
+        }
+
+        stack.push(index);
+        for (depend, _, _) in resolution.depends(plugins, index) {
+            if let Some(cycle) = visit(plugins, resolution, depend, stack, visited) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+
+        visited.push(index);
+        None
+    }
+
+    visit(plugins, resolution, index, &mut vec![], &mut vec![])
+}
+
+fn requirements<O: Send + Sync, I: Info>(
+    plugins: &[Plugin<'_, O, I>],
+    index: usize,
+) -> Vec<Requirement> {
+    let info = &plugins[index].info.info;
+    let depends = info.depends().iter().map(|depend| (depend, true));
+    let optional_depends = info.optional_depends().iter().map(|depend| (depend, false));
+
+    depends
+        .chain(optional_depends)
+        .map(|(depend, required)| Requirement {
+            plugin: index,
+            depend: depend.clone(),
+            required,
+        })
+        .collect()
+}
+
+// Перебор с возвратом: при ошибке `selected` возвращается в исходное состояние
+fn solve<O: Send + Sync, I: Info>(
+    plugins: &[Plugin<'_, O, I>],
+    selected: &mut Selected,
+    pending: &[Requirement],
+) -> Result<(), Box<DependencyConflict>> {
+    let (requirement, rest) = match pending.split_first() {
+        Some(pending) => pending,
+        None => return Ok(()),
+    };
+
+    let bundle = |index: usize| plugins[index].info.bundle.clone();
+    let depend = &requirement.depend;
+
+    if let Some((index, selected_by)) = selected.get(&depend.id) {
+        if *depend == plugins[*index].info.bundle || !requirement.required {
+            return solve(plugins, selected, rest);
+        }
+
+        return Err(Box::new(DependencyConflict::Incompatible {
+            plugin: bundle(requirement.plugin),
+            depend: depend.clone(),
+            selected: bundle(*index),
+            selected_by: selected_by
+                .as_ref()
+                .map(|(index, depend)| (bundle(*index), depend.clone())),
+        }));
+    }
+
+    // Сначала загруженные версии, затем более новые
+    let mut candidates: Vec<usize> = (0..plugins.len())
+        .filter(|index| *depend == plugins[*index].info.bundle)
+        .collect();
+    candidates.sort_by(|a, b| {
+        let (a, b) = (&plugins[*a], &plugins[*b]);
+        b.is_load
+            .cmp(&a.is_load)
+            .then_with(|| b.info.bundle.version.cmp(&a.info.bundle.version))
+    });
+
+    if candidates.is_empty() {
+        if !requirement.required {
+            return solve(plugins, selected, rest);
+        }
+
+        return Err(Box::new(DependencyConflict::Missing {
+            plugin: bundle(requirement.plugin),
+            depend: depend.clone(),
+            available: plugins
+                .iter()
+                .filter(|plugin| plugin.info.bundle.id == depend.id)
+                .map(|plugin| plugin.info.bundle.version.clone())
+                .collect(),
+        }));
+    }
+
+    let mut rejected = vec![];
+    for index in candidates {
+        selected.insert(
+            depend.id.clone(),
+            (index, Some((requirement.plugin, depend.clone()))),
+        );
+
+        let mut next = rest.to_vec();
+        next.extend(requirements(plugins, index));
+
+        match solve(plugins, selected, &next) {
+            Ok(_) => return Ok(()),
+            Err(conflict) => rejected.push((bundle(index), *conflict)),
+        }
+
+        selected.remove(&depend.id);
+    }
+
+    // Необязательную зависимость можно не загружать
+    if !requirement.required && solve(plugins, selected, rest).is_ok() {
+        return Ok(());
+    }
+
+    Err(Box::new(DependencyConflict::Rejected {
+        plugin: bundle(requirement.plugin),
+        depend: depend.clone(),
+        candidates: rejected,
+    }))
+}
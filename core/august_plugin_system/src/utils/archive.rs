@@ -1,27 +1,79 @@
 use std::{
+    collections::{hash_map::Entry, HashMap},
     ffi::OsStr,
     fs::File,
-    io::{Read, Write},
-    path::Path,
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
 };
 
 use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
 use crate::Bundle;
 
-use super::{BundleUnzipError, BundleZipError};
+#[cfg(feature = "signing")]
+use super::signing::{BundleSignature, SigningKey};
+use super::{
+    integrity::{Manifest, MANIFEST_FILE, SIGNATURE_FILE},
+    BundleUnzipError, BundleZipError,
+};
 
 pub fn zip<S, F>(
     path: &S,
     target_path: &str,
     compression_method: zip::CompressionMethod,
-    mut on_zip_file: F,
+    on_zip_file: F,
 ) -> Result<(), BundleZipError>
 where
     S: AsRef<OsStr> + ?Sized,
     F: FnMut(&Path),
 {
-    let path = Path::new(path);
+    zip_bundle(
+        Path::new(path),
+        target_path,
+        compression_method,
+        |_| Ok(None),
+        on_zip_file,
+    )
+}
+
+// Упаковка с подписью манифеста ключом автора
+#[cfg(feature = "signing")]
+pub fn zip_signed<S, F>(
+    path: &S,
+    target_path: &str,
+    compression_method: zip::CompressionMethod,
+    key: &SigningKey,
+    on_zip_file: F,
+) -> Result<(), BundleZipError>
+where
+    S: AsRef<OsStr> + ?Sized,
+    F: FnMut(&Path),
+{
+    zip_bundle(
+        Path::new(path),
+        target_path,
+        compression_method,
+        |manifest| Ok(Some(BundleSignature::sign(manifest, key).to_vec()?)),
+        on_zip_file,
+    )
+}
+
+// Подпись манифеста, если она есть, добавляется последним файлом
+fn zip_bundle<G, F>(
+    path: &Path,
+    target_path: &str,
+    compression_method: zip::CompressionMethod,
+    sign: G,
+    mut on_zip_file: F,
+) -> Result<(), BundleZipError>
+where
+    G: FnOnce(&[u8]) -> Result<Option<Vec<u8>>, BundleZipError>,
+    F: FnMut(&Path),
+{
     let target_path =
         Path::new(target_path).join(path.file_name().ok_or(BundleZipError::NoNameFailed)?);
 
@@ -40,6 +92,11 @@ where
                 .compression_method(compression_method)
                 .unix_permissions(0o755);
 
+            // Манифест собирается заново из упакованных файлов, бандл переносится из исходного
+            let mut manifest = Manifest {
+                bundle: Manifest::read(path)?.and_then(|manifest| manifest.bundle),
+                ..Manifest::new()
+            };
             let mut buffer = Vec::new();
             for entry in walkdir::WalkDir::new(path)
                 .into_iter()
@@ -47,6 +104,9 @@ where
             {
                 let entry_path = entry.path();
                 let name = entry_path.strip_prefix(path).unwrap();
+                if name == Path::new(MANIFEST_FILE) || name == Path::new(SIGNATURE_FILE) {
+                    continue;
+                }
 
                 if entry_path.is_file() {
                     #[allow(deprecated)]
@@ -55,6 +115,7 @@ where
 
                     f.read_to_end(&mut buffer)?;
                     archive.write_all(&buffer)?;
+                    manifest.insert(super::integrity::file_name(path, entry_path), &buffer);
                     buffer.clear();
                 } else if !name.as_os_str().is_empty() {
                     #[allow(deprecated)]
@@ -63,6 +124,17 @@ where
 
                 on_zip_file(name);
             }
+
+            let manifest = manifest.to_vec()?;
+            archive.start_file(MANIFEST_FILE, options)?;
+            archive.write_all(&manifest)?;
+            on_zip_file(Path::new(MANIFEST_FILE));
+
+            if let Some(signature) = sign(&manifest)? {
+                archive.start_file(SIGNATURE_FILE, options)?;
+                archive.write_all(&signature)?;
+                on_zip_file(Path::new(SIGNATURE_FILE));
+            }
         }),
     }
 }
@@ -71,9 +143,12 @@ pub fn unzip<S>(path: &S, target_path: &str) -> Result<Bundle, BundleUnzipError>
 where
     S: AsRef<OsStr> + ?Sized,
 {
-    let path = Path::new(path);
-    let target_path =
-        Path::new(target_path).join(path.file_name().ok_or(BundleUnzipError::NoNameFailed)?);
+    let target_path = extract(Path::new(path), Path::new(target_path))?;
+    Ok(Bundle::from_filename(target_path.file_name().unwrap())?)
+}
+
+fn extract(path: &Path, target_path: &Path) -> Result<PathBuf, BundleUnzipError> {
+    let target_path = target_path.join(path.file_name().ok_or(BundleUnzipError::NoNameFailed)?);
 
     if !path.is_file() {
         return Err(BundleUnzipError::MissingBundleFailed);
@@ -89,7 +164,117 @@ where
         }),
     }?;
 
-    Ok(Bundle::from_filename(target_path.file_name().unwrap())?)
+    Ok(target_path)
+}
+
+// Распаковка бандла в кэш загрузчика.
+// Каталог `<бандл>-<pid>-<счётчик>` принадлежит одной регистрации и удаляется
+// вместе с ней. Кэши процесса, завершившегося без удаления регистраций,
+// удаляет следующий процесс при первой распаковке.
+pub(crate) fn unzip_to_cache(path: &Path) -> Result<PathBuf, BundleUnzipError> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    // Блокировка `<pid>.lock` удерживается до завершения процесса
+    static PROCESS_LOCK: OnceLock<Option<File>> = OnceLock::new();
+
+    let root = std::env::temp_dir().join("august");
+    PROCESS_LOCK.get_or_init(|| {
+        let lock = lock_process(&root).ok();
+        // Без своей блокировки свои же кэши нельзя отличить от брошенных
+        if lock.is_some() {
+            remove_stale_caches(&root);
+        }
+        lock
+    });
+
+    // Имя архива не разбирается: бандл определяет способ именования загрузчика
+    let name = path.file_name().ok_or(BundleUnzipError::NoNameFailed)?;
+
+    let cache = root.join(format!(
+        "{}-{}-{}",
+        name.to_string_lossy(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&cache)?;
+
+    match extract(path, &cache) {
+        Ok(_) => Ok(cache),
+        Err(e) => {
+            std::fs::remove_dir_all(&cache)?;
+            Err(e)
+        }
+    }
+}
+
+fn lock_path(root: &Path, pid: u32) -> PathBuf {
+    root.join(format!("{pid}.lock"))
+}
+
+fn lock_process(root: &Path) -> std::io::Result<File> {
+    std::fs::create_dir_all(root)?;
+
+    let path = lock_path(root, std::process::id());
+    loop {
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        file.lock()?;
+
+        // Пока ждали блокировку, файл прежнего процесса с тем же pid мог быть удалён
+        if path.exists() {
+            return Ok(file);
+        }
+    }
+}
+
+// Владелец кэша завершился, если его блокировку удалось захватить или файла
+// блокировки нет вовсе: процесс создаёт его раньше своего первого кэша.
+fn remove_stale_caches(root: &Path) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+
+    // Блокировки завершившихся процессов удерживаются до конца очистки
+    let mut dead: HashMap<u32, Option<File>> = HashMap::new();
+    let mut alive = vec![std::process::id()];
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let pid = match path.is_dir().then(|| cache_owner(&path)).flatten() {
+            Some(pid) if !alive.contains(&pid) => pid,
+            _ => continue,
+        };
+
+        if let Entry::Vacant(entry) = dead.entry(pid) {
+            match File::open(lock_path(root, pid)) {
+                Ok(file) if file.try_lock().is_ok() => entry.insert(Some(file)),
+                Err(e) if e.kind() == ErrorKind::NotFound => entry.insert(None),
+                _ => {
+                    alive.push(pid);
+                    continue;
+                }
+            };
+        }
+
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    for (pid, lock) in dead {
+        if lock.is_some() {
+            let _ = std::fs::remove_file(lock_path(root, pid));
+        }
+    }
+}
+
+// Pid процесса из имени каталога `<бандл>-<pid>-<счётчик>`
+fn cache_owner(path: &Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    let mut parts = name.rsplitn(3, '-');
+    parts.next()?.parse::<usize>().ok()?;
+    let pid = parts.next()?.parse().ok()?;
+    parts.next().map(|_| pid)
 }
 
 #[test]
@@ -127,3 +312,31 @@ fn test_unzip() {
 
     std::fs::remove_dir_all(format!("{target_path}/{name}")).unwrap();
 }
+
+#[test]
+fn test_remove_stale_caches() {
+    let root = std::env::temp_dir().join("august_stale_cache_test");
+    if root.exists() {
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    let (own, alive, dead, orphan) = (std::process::id(), u32::MAX - 1, u32::MAX - 2, u32::MAX - 3);
+    for pid in [own, alive, dead, orphan] {
+        std::fs::create_dir_all(root.join(format!("plugin-v1.0.0.vpl-{pid}-0"))).unwrap();
+    }
+
+    // Блокировку живого процесса держит отдельный дескриптор
+    let alive_lock = File::create(lock_path(&root, alive)).unwrap();
+    alive_lock.lock().unwrap();
+    File::create(lock_path(&root, dead)).unwrap();
+
+    remove_stale_caches(&root);
+
+    let exists = |pid: u32| root.join(format!("plugin-v1.0.0.vpl-{pid}-0")).exists();
+    assert!(exists(own) && exists(alive));
+    assert!(!exists(dead) && !exists(orphan));
+    assert!(!lock_path(&root, dead).exists());
+
+    drop(alive_lock);
+    std::fs::remove_dir_all(&root).unwrap();
+}
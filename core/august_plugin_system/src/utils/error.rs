@@ -2,23 +2,41 @@ use semver::Version;
 use std::{
     error::Error as StdError,
     fmt::{Debug, Display},
+    path::PathBuf,
 };
 use thiserror::Error;
 
-use crate::{Bundle, Depend};
+use crate::{
+    variable::{Variable, VariableType},
+    Bundle, Depend, DependencyConflict,
+};
 
 #[derive(Error, Debug)]
 pub enum BundleFromError {
     #[error("Error converting OsStr to str")]
     OsStrToStrFailed,
-    #[error("Failed to get ID")]
-    IDFailed,
-    #[error("Failed to get version")]
-    VersionFailed,
-    #[error("Failed to get format")]
-    FormatFailed,
-    #[error("Failed to parse version")]
-    ParseVersion(#[from] semver::Error),
+    #[error("Path has no bundle name")]
+    NoName,
+    #[error("Failed to get ID from `{name}`: {reason}")]
+    IDFailed { name: String, reason: &'static str },
+    #[error("Failed to get version from `{name}`: {reason}")]
+    VersionFailed { name: String, reason: &'static str },
+    #[error("Failed to get format from `{name}`: {reason}")]
+    FormatFailed { name: String, reason: &'static str },
+    #[error("Failed to parse version of `{name}`")]
+    ParseVersion {
+        name: String,
+        #[source]
+        source: semver::Error,
+    },
+    #[error("Failed to read manifest `{}`", .path.display())]
+    ManifestFailed {
+        path: PathBuf,
+        #[source]
+        source: IntegrityError,
+    },
+    #[error("Manifest `{}` does not describe the bundle", .path.display())]
+    ManifestWithoutBundle { path: PathBuf },
 }
 
 #[cfg(feature = "archive")]
@@ -36,6 +54,8 @@ pub enum BundleZipError {
     OpenFileInBundleFailed(#[from] std::io::Error),
     #[error("Failed to zip")]
     ZipFailed(#[from] zip::result::ZipError),
+    #[error("Failed to create manifest")]
+    ManifestFailed(#[from] IntegrityError),
 }
 
 #[cfg(feature = "archive")]
@@ -69,6 +89,8 @@ pub enum RegisterManagerError {
     AlreadyOccupiedFormat(String),
     #[error("Manager registration error by the manager")]
     RegisterManagerByManager(#[from] Box<dyn StdError + Send + Sync>),
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
 }
 
 #[derive(Error, Debug)]
@@ -79,6 +101,8 @@ pub enum UnregisterManagerError {
     UnregisterPlugin(#[from] UnregisterPluginError),
     #[error("Manager unregistration error by the manager")]
     UnregisterManagerByManager(#[from] Box<dyn StdError + Send + Sync>),
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
 }
 
 #[derive(Error, Debug)]
@@ -93,6 +117,60 @@ pub enum RegisterPluginError {
     RegisterPluginByManager(#[from] Box<dyn StdError + Send + Sync>),
     #[error("A plugin with ID `{0}` and version `{1}` already exists")]
     AlreadyExistsIDAndVersion(String, Version),
+    #[cfg(feature = "archive")]
+    #[error("Failed to unzip bundle")]
+    UnzipFailed(#[from] BundleUnzipError),
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
+    #[error("File `{file}` does not match the bundle manifest")]
+    IntegrityMismatch { file: String },
+    #[error("Bundle is not signed")]
+    Unsigned,
+    #[error("Bundle is signed by an untrusted key `{key}`")]
+    UnknownSigner { key: String },
+    #[error("Bundle signature does not match its manifest")]
+    BadSignature,
+    #[error("Failed to verify bundle integrity")]
+    IntegrityFailed(#[source] IntegrityError),
+}
+
+impl From<IntegrityError> for RegisterPluginError {
+    fn from(e: IntegrityError) -> Self {
+        match e {
+            IntegrityError::Mismatch { file } => Self::IntegrityMismatch { file },
+            IntegrityError::Unsigned => Self::Unsigned,
+            IntegrityError::UnknownSigner { key } => Self::UnknownSigner { key },
+            IntegrityError::BadSignature => Self::BadSignature,
+            e => Self::IntegrityFailed(e),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum IntegrityError {
+    #[error("File `{file}` does not match the manifest")]
+    Mismatch { file: String },
+    #[error("Bundle does not contain a manifest")]
+    MissingManifest,
+    #[error("Bundle is not signed")]
+    Unsigned,
+    #[error("Manifest is signed by an untrusted key `{key}`")]
+    UnknownSigner { key: String },
+    #[error("Invalid manifest signature")]
+    BadSignature,
+    #[error("Invalid manifest")]
+    InvalidManifest(#[from] serde_json::Error),
+    #[error("Failed to read bundle")]
+    ReadFailed(#[from] std::io::Error),
+}
+
+#[cfg(feature = "discover")]
+#[derive(Error, Debug)]
+pub enum DiscoverPluginsError {
+    #[error("Invalid pattern `{0}`")]
+    InvalidPattern(String, #[source] glob::PatternError),
+    #[error("Failed to read directory `{}`", .0.display())]
+    ReadDirFailed(PathBuf, #[source] std::io::Error),
 }
 
 #[derive(Error, Debug)]
@@ -105,6 +183,8 @@ pub enum UnregisterPluginError {
     HasUnregisteredManager,
     #[error("Plugin unregistration error by the manager")]
     UnregisterPluginByManager(#[from] Box<dyn StdError + Send + Sync>),
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
 }
 
 #[derive(Error, Debug)]
@@ -122,6 +202,20 @@ pub enum LoadPluginError {
     LoadPluginByManager(#[from] Box<dyn StdError + Send + Sync>),
     #[error("Requests not found: {0:?}")]
     RequestsNotFound(Vec<String>),
+    #[error("Failed to resolve dependencies: {0}")]
+    DependencyConflict(Box<DependencyConflict>),
+    #[error("Dependency cycle detected: {}", .0.iter().map(|bundle| bundle.to_string()).collect::<Vec<_>>().join(" -> "))]
+    DependencyCycle(Vec<Bundle>),
+    #[error("The plugin has an unregistered manager")]
+    HasUnregisteredManager,
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
+}
+
+#[derive(Error, Debug)]
+pub enum ValidateLoaderError {
+    #[error("Invalid plugins: {0:?}")]
+    InvalidPlugins(Vec<(Bundle, LoadPluginError)>),
 }
 
 #[derive(Error, Debug)]
@@ -132,6 +226,34 @@ pub enum UnloadPluginError {
     CurrentlyUsesDepend { plugin: Bundle, depend: Bundle },
     #[error("Plugin unload error by the manager")]
     UnloadPluginByManager(#[from] Box<dyn StdError + Send + Sync>),
+    #[error("The plugin has an unregistered manager")]
+    HasUnregisteredManager,
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
+}
+
+#[derive(Error, Debug)]
+pub enum ReloadPluginError {
+    #[error("Not found plugin")]
+    NotFound,
+    #[error("Plugin unload error")]
+    UnloadFailed(#[from] UnloadPluginError),
+    #[error("Plugin load error")]
+    LoadFailed(#[from] LoadPluginError),
+    #[error("Failed to roll back the plugin after `{error}`: {rollback}")]
+    RollbackFailed {
+        error: LoadPluginError,
+        rollback: LoadPluginError,
+    },
+}
+
+#[cfg(feature = "hot-reload")]
+#[derive(Error, Debug)]
+pub enum HotReloadError {
+    #[error("Failed to watch plugin")]
+    WatchFailed(#[from] notify::Error),
+    #[error("Failed to copy plugin")]
+    SnapshotFailed(#[from] std::io::Error),
 }
 
 #[derive(Error, Debug)]
@@ -146,6 +268,20 @@ pub enum RegisterRequestError {
 pub enum PluginCallRequestError {
     #[error("Request not found")]
     NotFound,
+    #[error("The loader is locked by the current thread")]
+    LoaderLocked,
+}
+
+#[derive(Error, Debug)]
+pub enum TypedCallRequestError {
+    #[error("Request call failed")]
+    Request(#[from] PluginCallRequestError),
+    #[error("{0}")]
+    Plugin(Box<dyn std::error::Error + Send + Sync>),
+    #[error("Request did not return a value")]
+    MissingOutput,
+    #[error("Value conversion failed")]
+    Convert(#[from] SerdeVariableError),
 }
 
 #[derive(Error, Debug)]
@@ -160,6 +296,18 @@ pub enum PluginCallFunctionError {
     NotFound,
 }
 
+#[derive(Error, Debug)]
+pub enum CallArgumentsError {
+    #[error("Expected {expected} arguments, but {actual} were passed")]
+    Count { expected: usize, actual: usize },
+    #[error("Argument `{name}` (#{index}) must be of type `{expected}`")]
+    Type {
+        index: usize,
+        name: String,
+        expected: VariableType,
+    },
+}
+
 #[derive(Error, Debug)]
 pub enum CallFunctionDependError {
     #[error("Depend not found")]
@@ -168,6 +316,34 @@ pub enum CallFunctionDependError {
     FailedCallFunction(#[from] PluginCallFunctionError),
 }
 
+#[derive(Error, Debug)]
+pub enum CoerceVariableError {
+    #[error("Value `{value}` cannot be converted to type `{to}`")]
+    Incompatible { value: Variable, to: VariableType },
+    #[error("Value `{value}` does not fit into type `{to}`")]
+    OutOfRange { value: Variable, to: VariableType },
+}
+
+#[derive(Error, Debug)]
+pub enum SerdeVariableError {
+    #[error("{0}")]
+    Custom(String),
+    #[error("Map key must be a string")]
+    KeyMustBeString,
+}
+
+impl serde::ser::Error for SerdeVariableError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for SerdeVariableError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
 pub type ManagerResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
 #[derive(Debug)]
@@ -0,0 +1,161 @@
+#[cfg(feature = "integrity")]
+use std::path::PathBuf;
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "integrity")]
+use sha2::{Digest, Sha256};
+
+use crate::Bundle;
+
+use super::IntegrityError;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const SIGNATURE_FILE: &str = "manifest.sig";
+
+// Политика проверки манифеста при регистрации плагина
+#[cfg(feature = "integrity")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityPolicy {
+    #[default]
+    Off,
+    Warn,
+    Enforce,
+}
+
+// Манифест бандла: относительный путь файла и его SHA-256.
+// Бандл указывается для именования через `ManifestNaming`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<Bundle>,
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(path: &Path) -> Result<Option<Self>, IntegrityError> {
+        let path = path.join(MANIFEST_FILE);
+        match path.is_file() {
+            true => Ok(Some(serde_json::from_slice(&fs::read(path)?)?)),
+            false => Ok(None),
+        }
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>, IntegrityError> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+}
+
+// Хеширование и проверка файлов доступны с возможностью `integrity`
+#[cfg(feature = "integrity")]
+impl Manifest {
+    // Хеширует все файлы бандла, кроме манифеста и его подписи
+    pub fn from_dir(path: &Path) -> Result<Self, IntegrityError> {
+        let mut manifest = Self::new();
+        for file in walk_files(path)? {
+            let name = file_name(path, &file);
+            if name != MANIFEST_FILE && name != SIGNATURE_FILE {
+                manifest.insert(name, &fs::read(&file)?);
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn insert(&mut self, name: String, content: &[u8]) {
+        self.files.insert(name, hash(content));
+    }
+
+    // Сверяет манифест с содержимым каталога бандла.
+    // Лишний, отсутствующий или изменённый файл считается расхождением.
+    pub fn verify(&self, path: &Path) -> Result<(), IntegrityError> {
+        let actual = Self::from_dir(path)?;
+
+        let mismatch = self
+            .files
+            .iter()
+            .find(|(name, hash)| actual.files.get(*name) != Some(hash))
+            .map(|(name, _)| name)
+            .or_else(|| {
+                actual
+                    .files
+                    .keys()
+                    .find(|name| !self.files.contains_key(*name))
+            });
+
+        match mismatch {
+            Some(file) => Err(IntegrityError::Mismatch { file: file.clone() }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "integrity")]
+pub fn hash(content: &[u8]) -> String {
+    to_hex(&Sha256::digest(content))
+}
+
+#[cfg(feature = "integrity")]
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(feature = "signing")]
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|byte| match byte.len() {
+            2 => u8::from_str_radix(std::str::from_utf8(byte).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+// Путь файла относительно бандла, одинаковый на всех платформах
+#[cfg(feature = "integrity")]
+pub(crate) fn file_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap()
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(feature = "integrity")]
+fn walk_files(path: &Path) -> Result<Vec<PathBuf>, IntegrityError> {
+    let mut files = vec![];
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => files.append(&mut walk_files(&path)?),
+            false => files.push(path),
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(feature = "integrity")]
+#[test]
+fn verify_manifest() {
+    let path = Path::new("../../plugins/dependency/dep_1-v1.0.0.vpl");
+    let mut manifest = Manifest::from_dir(path).unwrap();
+    assert!(manifest.files.contains_key("config.toml"));
+    assert!(manifest.verify(path).is_ok());
+
+    manifest.insert("config.toml".to_string(), b"tampered");
+    assert!(matches!(
+        manifest.verify(path),
+        Err(IntegrityError::Mismatch { file }) if file == "config.toml"
+    ));
+
+    manifest.files.clear();
+    assert!(matches!(
+        manifest.verify(path),
+        Err(IntegrityError::Mismatch { .. })
+    ));
+}
@@ -0,0 +1,218 @@
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+use parking_lot::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+thread_local! {
+    // Блокировки, удерживаемые текущим потоком: адрес и признак записи
+    static HELD: RefCell<Vec<(usize, bool)>> = const { RefCell::new(vec![]) };
+}
+
+struct Held {
+    address: usize,
+    write: bool,
+}
+
+impl Held {
+    fn new(address: usize, write: bool) -> Self {
+        HELD.with(|held| held.borrow_mut().push((address, write)));
+        Self { address, write }
+    }
+
+    fn find(address: usize) -> Option<bool> {
+        HELD.with(|held| {
+            held.borrow()
+                .iter()
+                .filter(|(a, _)| *a == address)
+                .map(|(_, write)| *write)
+                .reduce(|a, b| a || b)
+        })
+    }
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(index) = held.iter().rposition(|h| *h == (self.address, self.write)) {
+                held.remove(index);
+            }
+        });
+    }
+}
+
+// Блокировки потока, переданные задачам пула rayon. Иначе задача,
+// обратившаяся к загрузчику, ждала бы блокировку, удерживаемую вызвавшим её потоком.
+pub(crate) struct HeldLocks(Vec<(usize, bool)>);
+
+impl HeldLocks {
+    pub(crate) fn current() -> Self {
+        HELD.with(|held| Self(held.borrow().clone()))
+    }
+
+    pub(crate) fn enter(&self) -> HeldScope {
+        HeldScope {
+            _held: self
+                .0
+                .iter()
+                .map(|(address, write)| Held::new(*address, *write))
+                .collect(),
+        }
+    }
+}
+
+pub(crate) struct HeldScope {
+    _held: Vec<Held>,
+}
+
+// Блокировка, которая вместо взаимоблокировки сообщает о повторном
+// захвате в том же потоке (например, из плагина во время его загрузки)
+pub(crate) struct Lock<T> {
+    lock: RwLock<T>,
+}
+
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            lock: RwLock::new(value),
+        }
+    }
+
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    pub(crate) fn is_held(&self) -> bool {
+        Held::find(self.address()).is_some()
+    }
+
+    pub(crate) fn read(&self) -> Option<LoaderRef<'_, T>> {
+        if Held::find(self.address()) == Some(true) {
+            return None;
+        }
+
+        // Рекурсивное чтение не ждёт писателей, поэтому плагин может
+        // обращаться к загрузчику из вызванной хостом функции
+        let guard = self.lock.read_recursive();
+        Some(LoaderRef {
+            guard: RwLockReadGuard::map(guard, |value| value),
+            _held: Held::new(self.address(), false),
+        })
+    }
+
+    // Для владельца загрузчика: заимствование `Loader` исключает
+    // повторный захват в том же потоке, поэтому поток не проверяется
+    pub(crate) fn read_blocking(&self) -> LoaderRef<'_, T> {
+        LoaderRef {
+            guard: RwLockReadGuard::map(self.lock.read_recursive(), |value| value),
+            _held: Held::new(self.address(), false),
+        }
+    }
+
+    pub(crate) fn write_blocking(&self) -> LoaderMut<'_, T> {
+        LoaderMut {
+            guard: RwLockWriteGuard::map(self.lock.write(), |value| value),
+            _held: Held::new(self.address(), true),
+        }
+    }
+
+    pub(crate) fn write(&self) -> Option<LoaderMut<'_, T>> {
+        if self.is_held() {
+            return None;
+        }
+
+        let guard = self.lock.write();
+        Some(LoaderMut {
+            guard: RwLockWriteGuard::map(guard, |value| value),
+            _held: Held::new(self.address(), true),
+        })
+    }
+}
+
+/// Часть загрузчика, заблокированная на чтение.
+///
+/// Пока страж жив, загрузчик нельзя изменить: методы `Loader` с `&mut self`
+/// не компилируются, а изменяющие методы `Api` в этом потоке возвращают `LoaderLocked`.
+pub struct LoaderRef<'g, T: ?Sized> {
+    guard: MappedRwLockReadGuard<'g, T>,
+    _held: Held,
+}
+
+impl<'g, T: ?Sized> LoaderRef<'g, T> {
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> LoaderRef<'g, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        LoaderRef {
+            guard: MappedRwLockReadGuard::map(this.guard, f),
+            _held: this._held,
+        }
+    }
+
+    pub fn filter_map<U: ?Sized, F>(this: Self, f: F) -> Option<LoaderRef<'g, U>>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        let _held = this._held;
+        MappedRwLockReadGuard::try_map(this.guard, f)
+            .ok()
+            .map(|guard| LoaderRef { guard, _held })
+    }
+}
+
+impl<T: ?Sized> Deref for LoaderRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+/// Часть загрузчика, заблокированная на запись.
+///
+/// Пока страж жив, другие потоки ждут блокировку, а `Api` в этом потоке
+/// возвращает `LoaderLocked` (или `None`) для любого обращения к загрузчику.
+pub struct LoaderMut<'g, T: ?Sized> {
+    guard: MappedRwLockWriteGuard<'g, T>,
+    _held: Held,
+}
+
+impl<'g, T: ?Sized> LoaderMut<'g, T> {
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> LoaderMut<'g, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        LoaderMut {
+            guard: MappedRwLockWriteGuard::map(this.guard, f),
+            _held: this._held,
+        }
+    }
+
+    pub fn filter_map<U: ?Sized, F>(this: Self, f: F) -> Option<LoaderMut<'g, U>>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let _held = this._held;
+        MappedRwLockWriteGuard::try_map(this.guard, f)
+            .ok()
+            .map(|guard| LoaderMut { guard, _held })
+    }
+}
+
+impl<T: ?Sized> Deref for LoaderMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized> DerefMut for LoaderMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
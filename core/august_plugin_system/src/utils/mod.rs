@@ -1,8 +1,18 @@
 mod error;
-mod ptr;
+mod lock;
+
+pub mod integrity;
+#[cfg(feature = "signing")]
+pub mod signing;
 
 pub use error::*;
-pub use ptr::*;
+#[cfg(feature = "integrity")]
+pub use integrity::IntegrityPolicy;
+pub use integrity::Manifest;
+pub(crate) use lock::{HeldLocks, Lock};
+pub use lock::{LoaderMut, LoaderRef};
+#[cfg(feature = "signing")]
+pub use signing::TrustStore;
 
 #[cfg(feature = "archive")]
-pub mod archive;
\ No newline at end of file
+pub mod archive;
@@ -0,0 +1,152 @@
+use std::{fs, path::Path};
+
+use ed25519_dalek::{Signature, Signer};
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+pub use super::integrity::SIGNATURE_FILE;
+use super::{
+    integrity::{from_hex, to_hex, Manifest, MANIFEST_FILE},
+    IntegrityError,
+};
+
+pub fn generate_key() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
+
+// Открытые ключи авторов, которым доверяет загрузчик
+#[derive(Debug, Default, Clone)]
+pub struct TrustStore {
+    keys: Vec<VerifyingKey>,
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, key: VerifyingKey) {
+        if !self.contains(&key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn remove(&mut self, key: &VerifyingKey) -> bool {
+        let len = self.keys.len();
+        self.keys.retain(|k| k != key);
+        len != self.keys.len()
+    }
+
+    pub fn contains(&self, key: &VerifyingKey) -> bool {
+        self.keys.contains(key)
+    }
+
+    pub fn keys(&self) -> &[VerifyingKey] {
+        &self.keys
+    }
+}
+
+impl FromIterator<VerifyingKey> for TrustStore {
+    fn from_iter<T: IntoIterator<Item = VerifyingKey>>(iter: T) -> Self {
+        let mut store = Self::new();
+        iter.into_iter().for_each(|key| store.add(key));
+        store
+    }
+}
+
+// Подпись манифеста вместе с открытым ключом автора
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub key: String,
+    pub signature: String,
+}
+
+impl BundleSignature {
+    pub fn sign(manifest: &[u8], key: &SigningKey) -> Self {
+        Self {
+            key: to_hex(key.verifying_key().as_bytes()),
+            signature: to_hex(&key.sign(manifest).to_bytes()),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Option<Self>, IntegrityError> {
+        let path = path.join(SIGNATURE_FILE);
+        match path.is_file() {
+            true => Ok(Some(serde_json::from_slice(&fs::read(path)?)?)),
+            false => Ok(None),
+        }
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>, IntegrityError> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn verify(&self, manifest: &[u8], store: &TrustStore) -> Result<(), IntegrityError> {
+        let key = from_hex(&self.key)
+            .and_then(|key| key.try_into().ok())
+            .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+            .ok_or(IntegrityError::BadSignature)?;
+
+        if !store.contains(&key) {
+            return Err(IntegrityError::UnknownSigner {
+                key: self.key.clone(),
+            });
+        }
+
+        let signature = from_hex(&self.signature)
+            .and_then(|signature| Signature::from_slice(&signature).ok())
+            .ok_or(IntegrityError::BadSignature)?;
+
+        key.verify_strict(manifest, &signature)
+            .map_err(|_| IntegrityError::BadSignature)
+    }
+}
+
+// Пересобирает манифест каталога бандла и подписывает его
+pub fn sign_bundle(path: &Path, key: &SigningKey) -> Result<(), IntegrityError> {
+    let mut manifest = Manifest::from_dir(path)?;
+    manifest.bundle = Manifest::read(path)?.and_then(|manifest| manifest.bundle);
+
+    let manifest = manifest.to_vec()?;
+    fs::write(path.join(MANIFEST_FILE), &manifest)?;
+    fs::write(
+        path.join(SIGNATURE_FILE),
+        BundleSignature::sign(&manifest, key).to_vec()?,
+    )?;
+    Ok(())
+}
+
+// Проверяет автора манифеста, а затем содержимое бандла
+pub fn verify_bundle(path: &Path, store: &TrustStore) -> Result<(), IntegrityError> {
+    let signature = BundleSignature::read(path)?.ok_or(IntegrityError::Unsigned)?;
+
+    let manifest_path = path.join(MANIFEST_FILE);
+    if !manifest_path.is_file() {
+        return Err(IntegrityError::MissingManifest);
+    }
+
+    let manifest = fs::read(manifest_path)?;
+    signature.verify(&manifest, store)?;
+    serde_json::from_slice::<Manifest>(&manifest)?.verify(path)
+}
+
+#[test]
+fn sign_manifest() {
+    let key = generate_key();
+    let other = generate_key();
+    let store: TrustStore = [key.verifying_key()].into_iter().collect();
+
+    let signature = BundleSignature::sign(b"manifest", &key);
+    assert!(signature.verify(b"manifest", &store).is_ok());
+    assert!(matches!(
+        signature.verify(b"tampered", &store),
+        Err(IntegrityError::BadSignature)
+    ));
+
+    let signature = BundleSignature::sign(b"manifest", &other);
+    assert!(matches!(
+        signature.verify(b"manifest", &store),
+        Err(IntegrityError::UnknownSigner { key }) if key == to_hex(other.verifying_key().as_bytes())
+    ));
+}
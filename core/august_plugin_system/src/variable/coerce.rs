@@ -0,0 +1,306 @@
+use crate::utils::CoerceVariableError;
+
+use super::{
+    Variable, VariableFloatType, VariableIntType, VariableSignedIntType, VariableType,
+    VariableUnsignedIntType,
+};
+
+// Правила приведения чисел. Расширение без потерь разрешено всегда.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoercionPolicy {
+    // Только расширение без потерь
+    Widening,
+    // Сужение с проверкой переполнения, целые и дробные - без потери точности
+    #[default]
+    Checked,
+    // Как `Checked`, но дробная часть отбрасывается, а точность может теряться
+    Truncating,
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Variable {
+    pub fn coerce_to(&self, ty: &VariableType) -> Result<Variable, CoerceVariableError> {
+        self.coerce_to_with(ty, CoercionPolicy::default())
+    }
+
+    pub fn coerce_to_with(
+        &self,
+        ty: &VariableType,
+        policy: CoercionPolicy,
+    ) -> Result<Variable, CoerceVariableError> {
+        let incompatible = || CoerceVariableError::Incompatible {
+            value: self.clone(),
+            to: ty.clone(),
+        };
+
+        match (ty, self) {
+            (VariableType::Let, _) => Ok(self.clone()),
+            (VariableType::Optional(_), Variable::Null) => Ok(Variable::Null),
+            (VariableType::Optional(ty), var) => var.coerce_to_with(ty, policy),
+            (VariableType::List(ty), Variable::List(list)) => Ok(Variable::List(
+                list.iter()
+                    .map(|var| var.coerce_to_with(ty, policy))
+                    .collect::<Result<_, _>>()?,
+            )),
+            (VariableType::Int(to), var) => match var.number() {
+                Some(Number::Int(value)) => match is_lossless(var, ty) {
+                    true => Ok(int(value, *to).unwrap()),
+                    false if policy == CoercionPolicy::Widening => Err(incompatible()),
+                    false => int(value, *to).ok_or_else(|| self.out_of_range(ty)),
+                },
+                Some(Number::Float(value)) => {
+                    let value = match policy {
+                        CoercionPolicy::Widening => return Err(incompatible()),
+                        CoercionPolicy::Checked if value.fract() != 0.0 => {
+                            return Err(self.out_of_range(ty))
+                        }
+                        _ => value.trunc(),
+                    };
+
+                    // Значения за пределами i128 и NaN не поместятся ни в один тип
+                    match value.is_finite() && value.abs() < 2_f64.powi(127) {
+                        true => int(value as i128, *to).ok_or_else(|| self.out_of_range(ty)),
+                        false => Err(self.out_of_range(ty)),
+                    }
+                }
+                None => Err(incompatible()),
+            },
+            (VariableType::Float(to), var) => match var.number() {
+                Some(number) if is_lossless(var, ty) => Ok(float(number, *to)),
+                Some(_) if policy == CoercionPolicy::Widening => Err(incompatible()),
+                Some(number) if policy == CoercionPolicy::Truncating => Ok(float(number, *to)),
+                Some(number) => {
+                    let result = float(number, *to);
+                    let is_exact = match (number, &result) {
+                        (Number::Int(value), Variable::F32(x)) => *x as i128 == value,
+                        (Number::Int(value), Variable::F64(x)) => *x as i128 == value,
+                        (Number::Float(value), Variable::F32(x)) => {
+                            *x as f64 == value || !value.is_finite()
+                        }
+                        _ => true,
+                    };
+
+                    match is_exact {
+                        true => Ok(result),
+                        false => Err(self.out_of_range(ty)),
+                    }
+                }
+                None => Err(incompatible()),
+            },
+            (ty, var) if ty.matches(var) => Ok(var.clone()),
+            _ => Err(incompatible()),
+        }
+    }
+
+    fn number(&self) -> Option<Number> {
+        match *self {
+            Variable::I8(x) => Some(Number::Int(x as i128)),
+            Variable::I16(x) => Some(Number::Int(x as i128)),
+            Variable::I32(x) => Some(Number::Int(x as i128)),
+            Variable::I64(x) => Some(Number::Int(x as i128)),
+            Variable::U8(x) => Some(Number::Int(x as i128)),
+            Variable::U16(x) => Some(Number::Int(x as i128)),
+            Variable::U32(x) => Some(Number::Int(x as i128)),
+            Variable::U64(x) => Some(Number::Int(x as i128)),
+            Variable::F32(x) => Some(Number::Float(x as f64)),
+            Variable::F64(x) => Some(Number::Float(x)),
+            _ => None,
+        }
+    }
+
+    fn out_of_range(&self, ty: &VariableType) -> CoerceVariableError {
+        CoerceVariableError::OutOfRange {
+            value: self.clone(),
+            to: ty.clone(),
+        }
+    }
+}
+
+// Любое значение исходного типа представимо в целевом
+fn is_lossless(var: &Variable, ty: &VariableType) -> bool {
+    // Диапазон целых и число значащих бит дробных
+    fn bounds(ty: &VariableType) -> (i128, i128, u32) {
+        match *ty {
+            VariableType::I8 => (i8::MIN as i128, i8::MAX as i128, 0),
+            VariableType::I16 => (i16::MIN as i128, i16::MAX as i128, 0),
+            VariableType::I32 => (i32::MIN as i128, i32::MAX as i128, 0),
+            VariableType::I64 => (i64::MIN as i128, i64::MAX as i128, 0),
+            VariableType::U8 => (0, u8::MAX as i128, 0),
+            VariableType::U16 => (0, u16::MAX as i128, 0),
+            VariableType::U32 => (0, u32::MAX as i128, 0),
+            VariableType::U64 => (0, u64::MAX as i128, 0),
+            VariableType::F32 => (-(1 << f32::MANTISSA_DIGITS), 1 << f32::MANTISSA_DIGITS, 24),
+            VariableType::F64 => (-(1 << f64::MANTISSA_DIGITS), 1 << f64::MANTISSA_DIGITS, 53),
+            _ => (0, 0, 0),
+        }
+    }
+
+    let from = match var {
+        Variable::I8(_) => VariableType::I8,
+        Variable::I16(_) => VariableType::I16,
+        Variable::I32(_) => VariableType::I32,
+        Variable::I64(_) => VariableType::I64,
+        Variable::U8(_) => VariableType::U8,
+        Variable::U16(_) => VariableType::U16,
+        Variable::U32(_) => VariableType::U32,
+        Variable::U64(_) => VariableType::U64,
+        Variable::F32(_) => VariableType::F32,
+        Variable::F64(_) => VariableType::F64,
+        _ => return false,
+    };
+
+    let (from_min, from_max, from_bits) = bounds(&from);
+    let (to_min, to_max, to_bits) = bounds(ty);
+
+    match (from, ty) {
+        (VariableType::Float(_), VariableType::Float(_)) => from_bits <= to_bits,
+        (VariableType::Float(_), _) => false,
+        _ => to_min <= from_min && from_max <= to_max,
+    }
+}
+
+fn int(value: i128, ty: VariableIntType) -> Option<Variable> {
+    match ty {
+        VariableIntType::Signed(ty) => match ty {
+            VariableSignedIntType::I8 => i8::try_from(value).ok().map(Variable::I8),
+            VariableSignedIntType::I16 => i16::try_from(value).ok().map(Variable::I16),
+            VariableSignedIntType::I32 => i32::try_from(value).ok().map(Variable::I32),
+            VariableSignedIntType::I64 => i64::try_from(value).ok().map(Variable::I64),
+        },
+        VariableIntType::Unsigned(ty) => match ty {
+            VariableUnsignedIntType::U8 => u8::try_from(value).ok().map(Variable::U8),
+            VariableUnsignedIntType::U16 => u16::try_from(value).ok().map(Variable::U16),
+            VariableUnsignedIntType::U32 => u32::try_from(value).ok().map(Variable::U32),
+            VariableUnsignedIntType::U64 => u64::try_from(value).ok().map(Variable::U64),
+        },
+    }
+}
+
+fn float(number: Number, ty: VariableFloatType) -> Variable {
+    match (ty, number) {
+        (VariableFloatType::F32, Number::Int(value)) => Variable::F32(value as f32),
+        (VariableFloatType::F32, Number::Float(value)) => Variable::F32(value as f32),
+        (VariableFloatType::F64, Number::Int(value)) => Variable::F64(value as f64),
+        (VariableFloatType::F64, Number::Float(value)) => Variable::F64(value),
+    }
+}
+
+#[test]
+fn coerce_widening() {
+    let policy = CoercionPolicy::Widening;
+
+    let var = Variable::I64(10);
+    assert!(var.coerce_to_with(&VariableType::I32, policy).is_err());
+    assert!(var.coerce_to_with(&VariableType::F64, policy).is_err());
+
+    let var = Variable::I32(10);
+    assert_eq!(
+        var.coerce_to_with(&VariableType::I64, policy).unwrap(),
+        Variable::I64(10)
+    );
+    assert_eq!(
+        var.coerce_to_with(&VariableType::F64, policy).unwrap(),
+        Variable::F64(10.0)
+    );
+    assert!(var.coerce_to_with(&VariableType::F32, policy).is_err());
+    assert_eq!(
+        Variable::U8(255)
+            .coerce_to_with(&VariableType::I16, policy)
+            .unwrap(),
+        Variable::I16(255)
+    );
+    assert_eq!(
+        Variable::F32(1.5)
+            .coerce_to_with(&VariableType::F64, policy)
+            .unwrap(),
+        Variable::F64(1.5)
+    );
+}
+
+#[test]
+fn coerce_checked() {
+    assert_eq!(
+        Variable::I64(10).coerce_to(&VariableType::I32).unwrap(),
+        Variable::I32(10)
+    );
+    assert!(matches!(
+        Variable::I64(1 << 40).coerce_to(&VariableType::I32),
+        Err(CoerceVariableError::OutOfRange { .. })
+    ));
+    assert!(Variable::I32(-1).coerce_to(&VariableType::U32).is_err());
+
+    assert_eq!(
+        Variable::F64(3.0).coerce_to(&VariableType::I32).unwrap(),
+        Variable::I32(3)
+    );
+    assert!(Variable::F64(3.5).coerce_to(&VariableType::I32).is_err());
+    assert!(Variable::F64(f64::NAN)
+        .coerce_to(&VariableType::I32)
+        .is_err());
+
+    assert_eq!(
+        Variable::I64(1 << 24)
+            .coerce_to(&VariableType::F32)
+            .unwrap(),
+        Variable::F32(16777216.0)
+    );
+    assert!(Variable::I64((1 << 24) + 1)
+        .coerce_to(&VariableType::F32)
+        .is_err());
+    assert!(Variable::F64(0.1).coerce_to(&VariableType::F32).is_err());
+
+    assert!(matches!(
+        Variable::String("1".into()).coerce_to(&VariableType::I32),
+        Err(CoerceVariableError::Incompatible { .. })
+    ));
+}
+
+#[test]
+fn coerce_truncating() {
+    let policy = CoercionPolicy::Truncating;
+
+    assert_eq!(
+        Variable::F64(-3.7)
+            .coerce_to_with(&VariableType::I8, policy)
+            .unwrap(),
+        Variable::I8(-3)
+    );
+    assert!(Variable::F64(300.0)
+        .coerce_to_with(&VariableType::U8, policy)
+        .is_err());
+    assert_eq!(
+        Variable::F64(0.1)
+            .coerce_to_with(&VariableType::F32, policy)
+            .unwrap(),
+        Variable::F32(0.1)
+    );
+}
+
+#[test]
+fn coerce_nested() {
+    let list = Variable::List(vec![Variable::I64(1), Variable::F64(2.0)]);
+    assert_eq!(
+        list.coerce_to(&VariableType::list(VariableType::I32))
+            .unwrap(),
+        Variable::List(vec![Variable::I32(1), Variable::I32(2)])
+    );
+
+    let optional = VariableType::optional(VariableType::U8);
+    assert_eq!(Variable::Null.coerce_to(&optional).unwrap(), Variable::Null);
+    assert_eq!(
+        Variable::I64(7).coerce_to(&optional).unwrap(),
+        Variable::U8(7)
+    );
+
+    assert_eq!(
+        Variable::from("text")
+            .coerce_to(&VariableType::String)
+            .unwrap(),
+        Variable::from("text")
+    );
+}
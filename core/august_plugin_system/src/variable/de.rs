@@ -0,0 +1,280 @@
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeOwned, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::utils::SerdeVariableError;
+
+use super::Variable;
+
+// Структуры читаются как из `Map`, так и из позиционного `List`
+pub fn from_variable<T>(var: Variable) -> Result<T, SerdeVariableError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(var)
+}
+
+impl<'de> IntoDeserializer<'de, SerdeVariableError> for Variable {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Variable {
+    type Error = SerdeVariableError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Variable::Null => visitor.visit_unit(),
+            Variable::I8(v) => visitor.visit_i8(v),
+            Variable::I16(v) => visitor.visit_i16(v),
+            Variable::I32(v) => visitor.visit_i32(v),
+            Variable::I64(v) => visitor.visit_i64(v),
+            Variable::U8(v) => visitor.visit_u8(v),
+            Variable::U16(v) => visitor.visit_u16(v),
+            Variable::U32(v) => visitor.visit_u32(v),
+            Variable::U64(v) => visitor.visit_u64(v),
+            Variable::F32(v) => visitor.visit_f32(v),
+            Variable::F64(v) => visitor.visit_f64(v),
+            Variable::Bool(v) => visitor.visit_bool(v),
+            Variable::Char(v) => visitor.visit_char(v),
+            Variable::String(v) => visitor.visit_string(v),
+            Variable::Bytes(v) => visitor.visit_byte_buf(v.to_vec()),
+            Variable::List(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Variable::Map(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Variable::Null => visitor.visit_none(),
+            var => visitor.visit_some(var),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        // Вариант без данных - строка, с данными - словарь из одного элемента
+        let (variant, value) = match self {
+            Variable::String(variant) => (variant, None),
+            Variable::Map(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().unwrap();
+                (variant, Some(value))
+            }
+            var => {
+                return Err(de::Error::invalid_type(
+                    de::Unexpected::Other(&var.to_string()),
+                    &"string or map with a single key",
+                ))
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Variable>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = SerdeVariableError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer), SerdeVariableError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(Variable::String(self.variant))?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Variable>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = SerdeVariableError;
+
+    fn unit_variant(self) -> Result<(), SerdeVariableError> {
+        match self.value {
+            None | Some(Variable::Null) => Ok(()),
+            Some(var) => Err(de::Error::invalid_type(
+                de::Unexpected::Other(&var.to_string()),
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, SerdeVariableError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.value.unwrap_or_default())
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(self.value.unwrap_or_default(), visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeVariableError>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(self.value.unwrap_or_default(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::variable::{from_variable, to_variable, Variable};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(Point, u32),
+        Square { corner: Point, side: u32 },
+        Named(String),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Scene {
+        name: String,
+        shapes: Vec<Shape>,
+        tags: HashMap<String, bool>,
+        background: Option<(u8, u8, u8)>,
+    }
+
+    fn point(x: i32, y: i32) -> Variable {
+        Variable::Map(BTreeMap::from([
+            ("x".to_string(), x.into()),
+            ("y".to_string(), y.into()),
+        ]))
+    }
+
+    #[test]
+    fn round_trip() {
+        let scene = Scene {
+            name: "scene".to_string(),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(Point { x: 1, y: 2 }, 3),
+                Shape::Square {
+                    corner: Point { x: -1, y: 0 },
+                    side: 4,
+                },
+                Shape::Named("star".to_string()),
+            ],
+            tags: HashMap::from([("visible".to_string(), true)]),
+            background: Some((255, 255, 0)),
+        };
+
+        let var = to_variable(&scene).unwrap();
+        assert_eq!(from_variable::<Scene>(var).unwrap(), scene);
+    }
+
+    #[test]
+    fn representation() {
+        assert_eq!(to_variable(&Point { x: 1, y: 2 }).unwrap(), point(1, 2));
+        assert_eq!(to_variable(&Shape::Empty).unwrap(), "Empty".into());
+        assert_eq!(
+            to_variable(&Shape::Circle(Point { x: 1, y: 2 }, 3)).unwrap(),
+            Variable::Map(BTreeMap::from([(
+                "Circle".to_string(),
+                Variable::List(vec![point(1, 2), 3_u32.into()])
+            )]))
+        );
+        assert_eq!(to_variable(&None::<i32>).unwrap(), Variable::Null);
+    }
+
+    #[test]
+    fn from_plugin_values() {
+        // Структура из позиционного списка, числа другой разрядности
+        let var = Variable::List(vec![Variable::I64(1), Variable::U8(2)]);
+        assert_eq!(from_variable::<Point>(var).unwrap(), Point { x: 1, y: 2 });
+
+        let var = Variable::Map(BTreeMap::from([(
+            "Square".to_string(),
+            Variable::Map(BTreeMap::from([
+                ("corner".to_string(), point(0, 0)),
+                ("side".to_string(), Variable::I32(5)),
+            ])),
+        )]));
+        assert_eq!(
+            from_variable::<Shape>(var).unwrap(),
+            Shape::Square {
+                corner: Point { x: 0, y: 0 },
+                side: 5
+            }
+        );
+
+        assert!(from_variable::<Point>(Variable::I64(1)).is_err());
+        assert!(from_variable::<u8>(Variable::I64(256)).is_err());
+    }
+}
@@ -0,0 +1,500 @@
+use std::{mem::ManuallyDrop, ptr};
+
+use crate::utils::ParseVariableError;
+
+use super::{
+    Variable, VariableFloatType, VariableIntType, VariableSignedIntType, VariableType,
+    VariableUnsignedIntType,
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiVariableKind {
+    Let,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Bool,
+    Char,
+    String,
+    List,
+    Map,
+    Bytes,
+}
+
+// Тип переменной на границе FFI. Необязательный тип принимает также `Null`.
+// У списка `element` указывает на тип элементов, у остальных типов он нулевой.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FfiVariableType {
+    pub kind: FfiVariableKind,
+    pub optional: bool,
+    pub element: *const FfiVariableType,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiVariableTag {
+    Null,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Bool,
+    Char,
+    String,
+    List,
+    Map,
+    Bytes,
+}
+
+// Буфер, выделенный стороной, создавшей переменную.
+// Освобождать его должна та же сторона.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiBuffer<T> {
+    pub ptr: *mut T,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl<T> Clone for FfiBuffer<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FfiBuffer<T> {}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union FfiVariableData {
+    pub i8: i8,
+    pub i16: i16,
+    pub i32: i32,
+    pub i64: i64,
+    pub u8: u8,
+    pub u16: u16,
+    pub u32: u32,
+    pub u64: u64,
+    pub f32: f32,
+    pub f64: f64,
+    pub bool: bool,
+    pub char: u32,
+    pub string: FfiBuffer<u8>,
+    pub list: FfiBuffer<FfiVariable>,
+    pub map: FfiBuffer<FfiMapEntry>,
+    pub bytes: FfiBuffer<u8>,
+}
+
+#[repr(C)]
+pub struct FfiVariable {
+    pub tag: FfiVariableTag,
+    pub data: FfiVariableData,
+}
+
+#[repr(C)]
+pub struct FfiMapEntry {
+    pub key: FfiBuffer<u8>,
+    pub value: FfiVariable,
+}
+
+impl<T> FfiBuffer<T> {
+    fn from_vec(vec: Vec<T>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        Self {
+            ptr: vec.as_mut_ptr(),
+            len: vec.len(),
+            capacity: vec.capacity(),
+        }
+    }
+
+    unsafe fn into_vec(self) -> Vec<T> {
+        Vec::from_raw_parts(self.ptr, self.len, self.capacity)
+    }
+
+    unsafe fn as_slice(&self) -> &[T] {
+        match self.len {
+            0 => &[],
+            len => std::slice::from_raw_parts(self.ptr, len),
+        }
+    }
+}
+
+impl FfiVariable {
+    pub const NULL: FfiVariable = FfiVariable {
+        tag: FfiVariableTag::Null,
+        data: FfiVariableData { u64: 0 },
+    };
+
+    /// Копирует данные в `Variable`, не забирая владение буферами.
+    ///
+    /// # Safety
+    /// Буферы строки, списка, словаря и байтов должны быть валидны.
+    pub unsafe fn to_variable(&self) -> Result<Variable, ParseVariableError> {
+        let data = &self.data;
+        Ok(match self.tag {
+            FfiVariableTag::Null => Variable::Null,
+            FfiVariableTag::I8 => Variable::I8(data.i8),
+            FfiVariableTag::I16 => Variable::I16(data.i16),
+            FfiVariableTag::I32 => Variable::I32(data.i32),
+            FfiVariableTag::I64 => Variable::I64(data.i64),
+            FfiVariableTag::U8 => Variable::U8(data.u8),
+            FfiVariableTag::U16 => Variable::U16(data.u16),
+            FfiVariableTag::U32 => Variable::U32(data.u32),
+            FfiVariableTag::U64 => Variable::U64(data.u64),
+            FfiVariableTag::F32 => Variable::F32(data.f32),
+            FfiVariableTag::F64 => Variable::F64(data.f64),
+            FfiVariableTag::Bool => Variable::Bool(data.bool),
+            FfiVariableTag::Char => Variable::Char(
+                char::from_u32(data.char).ok_or(ParseVariableError::new("char"))?,
+            ),
+            FfiVariableTag::String => Variable::String(
+                std::str::from_utf8(data.string.as_slice())
+                    .map_err(|_| ParseVariableError::new("String"))?
+                    .to_string(),
+            ),
+            FfiVariableTag::List => Variable::List(
+                data.list
+                    .as_slice()
+                    .iter()
+                    .map(|var| var.to_variable())
+                    .collect::<Result<_, _>>()?,
+            ),
+            FfiVariableTag::Map => Variable::Map(
+                data.map
+                    .as_slice()
+                    .iter()
+                    .map(|entry| {
+                        let key = std::str::from_utf8(entry.key.as_slice())
+                            .map_err(|_| ParseVariableError::new("String"))?;
+                        Ok((key.to_string(), entry.value.to_variable()?))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            FfiVariableTag::Bytes => Variable::Bytes(data.bytes.as_slice().into()),
+        })
+    }
+
+    /// Забирает владение буферами и превращает переменную в `Variable`.
+    ///
+    /// # Safety
+    /// Переменная должна быть создана этой же стороной через `FfiVariable::from`.
+    pub unsafe fn into_variable(mut self) -> Result<Variable, ParseVariableError> {
+        let variable = self.to_variable();
+        free_variable(&mut self);
+        variable
+    }
+}
+
+impl From<Variable> for FfiVariable {
+    fn from(var: Variable) -> Self {
+        let (tag, data) = match var {
+            Variable::Null => (FfiVariableTag::Null, FfiVariableData { u64: 0 }),
+            Variable::I8(v) => (FfiVariableTag::I8, FfiVariableData { i8: v }),
+            Variable::I16(v) => (FfiVariableTag::I16, FfiVariableData { i16: v }),
+            Variable::I32(v) => (FfiVariableTag::I32, FfiVariableData { i32: v }),
+            Variable::I64(v) => (FfiVariableTag::I64, FfiVariableData { i64: v }),
+            Variable::U8(v) => (FfiVariableTag::U8, FfiVariableData { u8: v }),
+            Variable::U16(v) => (FfiVariableTag::U16, FfiVariableData { u16: v }),
+            Variable::U32(v) => (FfiVariableTag::U32, FfiVariableData { u32: v }),
+            Variable::U64(v) => (FfiVariableTag::U64, FfiVariableData { u64: v }),
+            Variable::F32(v) => (FfiVariableTag::F32, FfiVariableData { f32: v }),
+            Variable::F64(v) => (FfiVariableTag::F64, FfiVariableData { f64: v }),
+            Variable::Bool(v) => (FfiVariableTag::Bool, FfiVariableData { bool: v }),
+            Variable::Char(v) => (FfiVariableTag::Char, FfiVariableData { char: v as u32 }),
+            Variable::String(v) => (
+                FfiVariableTag::String,
+                FfiVariableData {
+                    string: FfiBuffer::from_vec(v.into_bytes()),
+                },
+            ),
+            Variable::List(v) => (
+                FfiVariableTag::List,
+                FfiVariableData {
+                    list: FfiBuffer::from_vec(v.into_iter().map(FfiVariable::from).collect()),
+                },
+            ),
+            Variable::Map(v) => (
+                FfiVariableTag::Map,
+                FfiVariableData {
+                    map: FfiBuffer::from_vec(
+                        v.into_iter()
+                            .map(|(key, value)| FfiMapEntry {
+                                key: FfiBuffer::from_vec(key.into_bytes()),
+                                value: FfiVariable::from(value),
+                            })
+                            .collect(),
+                    ),
+                },
+            ),
+            Variable::Bytes(v) => (
+                FfiVariableTag::Bytes,
+                FfiVariableData {
+                    bytes: FfiBuffer::from_vec(v.to_vec()),
+                },
+            ),
+        };
+
+        Self { tag, data }
+    }
+}
+
+/// Освобождает буферы переменной, созданной через `FfiVariable::from`,
+/// и делает её `Null`.
+///
+/// # Safety
+/// `var` должен указывать на переменную, созданную этой же стороной.
+pub unsafe extern "C" fn free_variable(var: *mut FfiVariable) {
+    let var = match var.as_mut() {
+        Some(var) => var,
+        None => return,
+    };
+
+    match var.tag {
+        FfiVariableTag::String => drop(var.data.string.into_vec()),
+        FfiVariableTag::Bytes => drop(var.data.bytes.into_vec()),
+        FfiVariableTag::List => {
+            let mut list = var.data.list.into_vec();
+            list.iter_mut().for_each(|var| free_variable(var));
+        }
+        FfiVariableTag::Map => {
+            let mut map = var.data.map.into_vec();
+            map.iter_mut().for_each(|entry| {
+                drop(entry.key.into_vec());
+                free_variable(&mut entry.value);
+            });
+        }
+        _ => (),
+    }
+
+    ptr::write(var, FfiVariable::NULL);
+}
+
+impl FfiVariableType {
+    pub const fn new(kind: FfiVariableKind) -> Self {
+        Self {
+            kind,
+            optional: false,
+            element: ptr::null(),
+        }
+    }
+
+    pub const fn list(element: &'static FfiVariableType) -> Self {
+        Self {
+            kind: FfiVariableKind::List,
+            optional: false,
+            element,
+        }
+    }
+
+    pub const fn optional(self) -> Self {
+        Self {
+            optional: true,
+            ..self
+        }
+    }
+
+    /// Восстанавливает `VariableType`, не забирая владение типами элементов.
+    ///
+    /// # Safety
+    /// `element` должен быть нулевым или указывать на валидный тип.
+    pub unsafe fn to_variable_type(&self) -> VariableType {
+        let ty = match self.kind {
+            FfiVariableKind::Let => VariableType::Let,
+            FfiVariableKind::I8 => VariableType::I8,
+            FfiVariableKind::I16 => VariableType::I16,
+            FfiVariableKind::I32 => VariableType::I32,
+            FfiVariableKind::I64 => VariableType::I64,
+            FfiVariableKind::U8 => VariableType::U8,
+            FfiVariableKind::U16 => VariableType::U16,
+            FfiVariableKind::U32 => VariableType::U32,
+            FfiVariableKind::U64 => VariableType::U64,
+            FfiVariableKind::F32 => VariableType::F32,
+            FfiVariableKind::F64 => VariableType::F64,
+            FfiVariableKind::Bool => VariableType::Bool,
+            FfiVariableKind::Char => VariableType::Char,
+            FfiVariableKind::String => VariableType::String,
+            FfiVariableKind::List => VariableType::list(match self.element.as_ref() {
+                Some(element) => element.to_variable_type(),
+                None => VariableType::Let,
+            }),
+            FfiVariableKind::Map => VariableType::Map,
+            FfiVariableKind::Bytes => VariableType::Bytes,
+        };
+
+        match self.optional {
+            true => VariableType::optional(ty),
+            false => ty,
+        }
+    }
+}
+
+// Типы элементов списков выделяются в куче и освобождаются через `free_variable_type`
+impl From<VariableType> for FfiVariableType {
+    fn from(ty: VariableType) -> Self {
+        let kind = match ty {
+            VariableType::Let => FfiVariableKind::Let,
+            VariableType::Int(VariableIntType::Signed(ty)) => match ty {
+                VariableSignedIntType::I8 => FfiVariableKind::I8,
+                VariableSignedIntType::I16 => FfiVariableKind::I16,
+                VariableSignedIntType::I32 => FfiVariableKind::I32,
+                VariableSignedIntType::I64 => FfiVariableKind::I64,
+            },
+            VariableType::Int(VariableIntType::Unsigned(ty)) => match ty {
+                VariableUnsignedIntType::U8 => FfiVariableKind::U8,
+                VariableUnsignedIntType::U16 => FfiVariableKind::U16,
+                VariableUnsignedIntType::U32 => FfiVariableKind::U32,
+                VariableUnsignedIntType::U64 => FfiVariableKind::U64,
+            },
+            VariableType::Float(VariableFloatType::F32) => FfiVariableKind::F32,
+            VariableType::Float(VariableFloatType::F64) => FfiVariableKind::F64,
+            VariableType::Bool => FfiVariableKind::Bool,
+            VariableType::Char => FfiVariableKind::Char,
+            VariableType::String => FfiVariableKind::String,
+            VariableType::List(ty) => {
+                return Self {
+                    kind: FfiVariableKind::List,
+                    optional: false,
+                    element: Box::into_raw(Box::new(Self::from(*ty))),
+                }
+            }
+            VariableType::Map => FfiVariableKind::Map,
+            VariableType::Bytes => FfiVariableKind::Bytes,
+            VariableType::Optional(ty) => return Self::from(*ty).optional(),
+        };
+
+        Self::new(kind)
+    }
+}
+
+/// Освобождает типы элементов, созданные через `FfiVariableType::from`.
+///
+/// # Safety
+/// `ty` должен указывать на тип, созданный этой же стороной.
+/// Статические типы плагинов освобождать нельзя.
+pub unsafe extern "C" fn free_variable_type(ty: *mut FfiVariableType) {
+    let ty = match ty.as_mut() {
+        Some(ty) => ty,
+        None => return,
+    };
+
+    if !ty.element.is_null() {
+        let mut element = Box::from_raw(ty.element as *mut FfiVariableType);
+        free_variable_type(element.as_mut());
+        ty.element = ptr::null();
+    }
+}
+
+#[cfg(test)]
+fn variable_strategy() -> impl proptest::strategy::Strategy<Value = Variable> {
+    use proptest::{
+        collection::{btree_map, vec},
+        num,
+        prelude::*,
+    };
+
+    let float32 = num::f32::NORMAL | num::f32::SUBNORMAL | num::f32::ZERO | num::f32::INFINITE;
+    let float64 = num::f64::NORMAL | num::f64::SUBNORMAL | num::f64::ZERO | num::f64::INFINITE;
+
+    let leaf = prop_oneof![
+        Just(Variable::Null),
+        any::<i8>().prop_map(Variable::I8),
+        any::<i16>().prop_map(Variable::I16),
+        any::<i32>().prop_map(Variable::I32),
+        any::<i64>().prop_map(Variable::I64),
+        any::<u8>().prop_map(Variable::U8),
+        any::<u16>().prop_map(Variable::U16),
+        any::<u32>().prop_map(Variable::U32),
+        any::<u64>().prop_map(Variable::U64),
+        float32.prop_map(Variable::F32),
+        float64.prop_map(Variable::F64),
+        any::<bool>().prop_map(Variable::Bool),
+        any::<char>().prop_map(Variable::Char),
+        any::<String>().prop_map(Variable::String),
+        vec(any::<u8>(), 0..32).prop_map(|v| Variable::Bytes(v.into())),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..8).prop_map(Variable::List),
+            btree_map(any::<String>(), inner, 0..8).prop_map(Variable::Map),
+        ]
+    })
+}
+
+#[cfg(test)]
+fn variable_type_strategy() -> impl proptest::strategy::Strategy<Value = VariableType> {
+    use proptest::prelude::*;
+
+    let leaf = prop_oneof![
+        Just(VariableType::Let),
+        Just(VariableType::I8),
+        Just(VariableType::I16),
+        Just(VariableType::I32),
+        Just(VariableType::I64),
+        Just(VariableType::U8),
+        Just(VariableType::U16),
+        Just(VariableType::U32),
+        Just(VariableType::U64),
+        Just(VariableType::F32),
+        Just(VariableType::F64),
+        Just(VariableType::Bool),
+        Just(VariableType::Char),
+        Just(VariableType::String),
+        Just(VariableType::Map),
+        Just(VariableType::Bytes),
+    ];
+
+    // Вложенные `Optional` на границе схлопываются в один флаг
+    leaf.prop_recursive(4, 8, 1, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(VariableType::list),
+            inner.prop_map(|ty| match ty.is_optional() {
+                true => ty,
+                false => VariableType::optional(ty),
+            }),
+        ]
+    })
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn variable_round_trip(var in variable_strategy()) {
+        let ffi = FfiVariable::from(var.clone());
+        proptest::prop_assert_eq!(unsafe { ffi.into_variable() }.unwrap(), var);
+    }
+
+    #[test]
+    fn variable_copy(var in variable_strategy()) {
+        let mut ffi = FfiVariable::from(var.clone());
+        proptest::prop_assert_eq!(unsafe { ffi.to_variable() }.unwrap(), var);
+
+        unsafe { free_variable(&mut ffi) };
+        proptest::prop_assert_eq!(ffi.tag, FfiVariableTag::Null);
+    }
+
+    #[test]
+    fn variable_type_round_trip(ty in variable_type_strategy()) {
+        let mut ffi = FfiVariableType::from(ty.clone());
+        let result = unsafe { ffi.to_variable_type() };
+
+        unsafe { free_variable_type(&mut ffi) };
+        proptest::prop_assert_eq!(result, ty);
+        proptest::prop_assert!(ffi.element.is_null());
+    }
+}
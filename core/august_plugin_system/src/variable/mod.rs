@@ -1,5 +1,15 @@
+mod coerce;
+mod de;
+mod ffi;
+mod ser;
 mod var;
 mod var_type;
+mod wrapper;
 
+pub use coerce::*;
+pub use de::*;
+pub use ffi::*;
+pub use ser::*;
 pub use var::*;
-pub use var_type::*;
\ No newline at end of file
+pub use var_type::*;
+pub use wrapper::*;
@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+
+use serde::{ser, Serialize};
+
+use crate::utils::SerdeVariableError;
+
+use super::Variable;
+
+// Структуры превращаются в `Map`, последовательности и кортежи - в `List`,
+// варианты перечислений с данными - в `Map` из одного элемента
+pub fn to_variable<T>(value: &T) -> Result<Variable, SerdeVariableError>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeVariant<SerializeList>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeVariant<SerializeMap>;
+
+    fn serialize_bool(self, v: bool) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::U64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Bytes(v.into()))
+    }
+
+    fn serialize_none(self) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Variable, SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Null)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Variable, SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Variable, SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(Variable::Map(BTreeMap::from([(
+            variant.to_string(),
+            to_variable(value)?,
+        )])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList, SerdeVariableError> {
+        Ok(SerializeList {
+            list: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList, SerdeVariableError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, SerdeVariableError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeList>, SerdeVariableError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<SerializeMap, SerdeVariableError> {
+        Ok(SerializeMap {
+            map: BTreeMap::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<SerializeMap, SerdeVariableError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeMap>, SerdeVariableError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub struct SerializeList {
+    list: Vec<Variable>,
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.list.push(to_variable(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::List(self.list))
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+pub struct SerializeMap {
+    map: BTreeMap<String, Variable>,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        // Ключами словаря могут быть только строки и символы
+        self.key = match to_variable(key)? {
+            Variable::String(key) => Some(key),
+            Variable::Char(key) => Some(key.to_string()),
+            _ => return Err(SerdeVariableError::KeyMustBeString),
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.key.take().ok_or(SerdeVariableError::KeyMustBeString)?;
+        self.map.insert(key, to_variable(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        Ok(Variable::Map(self.map))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.map.insert(key.to_string(), to_variable(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+pub struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> SerializeVariant<S> {
+    fn wrap(variant: &str, value: Variable) -> Variable {
+        Variable::Map(BTreeMap::from([(variant.to_string(), value)]))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeList> {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeMap> {
+    type Ok = Variable;
+    type Error = SerdeVariableError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeVariableError>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Variable, SerdeVariableError> {
+        let value = ser::SerializeMap::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
@@ -1,4 +1,8 @@
-use std::fmt::Display;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::Arc,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +26,8 @@ pub enum Variable {
     Char(char),
     String(String),
     List(Vec<Variable>),
+    Map(BTreeMap<String, Variable>),
+    Bytes(Arc<[u8]>),
 }
 
 pub trait FromVariable {
@@ -103,6 +109,44 @@ where
     }
 }
 
+impl<T> From<Option<T>> for Variable
+where
+    T: Into<Variable>,
+{
+    fn from(x: Option<T>) -> Self {
+        match x {
+            Some(x) => x.into(),
+            None => Self::Null,
+        }
+    }
+}
+
+impl<T> From<HashMap<String, T>> for Variable
+where
+    T: Into<Variable>,
+{
+    fn from(x: HashMap<String, T>) -> Self {
+        Self::Map(
+            x.into_iter()
+                .map(|(key, item)| (key, item.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<T> From<BTreeMap<String, T>> for Variable
+where
+    T: Into<Variable>,
+{
+    fn from(x: BTreeMap<String, T>) -> Self {
+        Self::Map(
+            x.into_iter()
+                .map(|(key, item)| (key, item.into()))
+                .collect(),
+        )
+    }
+}
+
 impl_from!(i8, I8);
 impl_from!(i16, I16);
 impl_from!(i32, I32);
@@ -116,6 +160,7 @@ impl_from!(f64, F64);
 impl_from!(bool, Bool);
 impl_from!(char, Char);
 impl_from!(String, String);
+impl_from!(Arc<[u8]>, Bytes);
 
 impl Display for Variable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -135,6 +180,17 @@ impl Display for Variable {
             Variable::Char(v) => write!(f, "{v}"),
             Variable::String(v) => write!(f, "{v}"),
             Variable::List(v) => write!(f, "{v:?}"),
+            Variable::Map(v) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in v.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key:?}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Variable::Bytes(v) => write!(f, "b\"{}\"", v.escape_ascii()),
         }
     }
 }
@@ -151,42 +207,42 @@ impl Variable {
 impl Variable {
     pub fn parse<F>(self) -> F::Output
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var(self).unwrap()
     }
 
     pub fn parse_ref<F>(&self) -> F::RefOutput<'_>
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var_ref(self).unwrap()
     }
 
     pub fn parse_mut<F>(&mut self) -> F::MutOutput<'_>
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var_mut(self).unwrap()
     }
 
     pub fn try_parse<F>(self) -> Result<F::Output, ParseVariableError>
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var(self)
     }
 
     pub fn try_parse_ref<F>(&self) -> Result<F::RefOutput<'_>, ParseVariableError>
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var_ref(self)
     }
 
     pub fn try_parse_mut<F>(&mut self) -> Result<F::MutOutput<'_>, ParseVariableError>
     where
-        F: FromVariable,
+        F: FromVariable + ?Sized,
     {
         F::from_var_mut(self)
     }
@@ -236,6 +292,11 @@ where
                 }
                 Ok(arr)
             }
+            // Байты разбираются поэлементно, например в `Vec<u8>`
+            Variable::Bytes(x) => x
+                .iter()
+                .map(|b| Variable::U8(*b).try_parse::<T>())
+                .collect(),
             _ => Err(ParseVariableError::new("Vec<T>")),
         }
     }
@@ -267,6 +328,139 @@ where
     }
 }
 
+impl<T> FromVariable for Option<T>
+where
+    T: FromVariable,
+{
+    type Output = Option<T::Output>;
+    type RefOutput<'a> = Option<T::RefOutput<'a>> where T: 'a;
+    type MutOutput<'a> = Option<T::MutOutput<'a>> where T: 'a;
+
+    fn from_var(var: Variable) -> Result<Self::Output, ParseVariableError> {
+        match var {
+            Variable::Null => Ok(None),
+            var => Ok(Some(var.try_parse::<T>()?)),
+        }
+    }
+
+    fn from_var_ref(var: &Variable) -> Result<Self::RefOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Null => Ok(None),
+            var => Ok(Some(var.try_parse_ref::<T>()?)),
+        }
+    }
+
+    fn from_var_mut(var: &mut Variable) -> Result<Self::MutOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Null => Ok(None),
+            var => Ok(Some(var.try_parse_mut::<T>()?)),
+        }
+    }
+}
+
+impl FromVariable for BTreeMap<String, Variable> {
+    type Output = Self;
+    type RefOutput<'a> = &'a Self;
+    type MutOutput<'a> = &'a mut Self;
+
+    fn from_var(var: Variable) -> Result<Self::Output, ParseVariableError> {
+        match var {
+            Variable::Map(x) => Ok(x),
+            _ => Err(ParseVariableError::new("BTreeMap<String, Variable>")),
+        }
+    }
+
+    fn from_var_ref(var: &Variable) -> Result<Self::RefOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Map(x) => Ok(x),
+            _ => Err(ParseVariableError::new("BTreeMap<String, Variable>")),
+        }
+    }
+
+    fn from_var_mut(var: &mut Variable) -> Result<Self::MutOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Map(x) => Ok(x),
+            _ => Err(ParseVariableError::new("BTreeMap<String, Variable>")),
+        }
+    }
+}
+
+impl<T> FromVariable for HashMap<String, T>
+where
+    T: FromVariable,
+{
+    type Output = HashMap<String, T::Output>;
+    type RefOutput<'a> = HashMap<&'a str, T::RefOutput<'a>> where T: 'a;
+    type MutOutput<'a> = HashMap<&'a str, T::MutOutput<'a>> where T: 'a;
+
+    fn from_var(var: Variable) -> Result<Self::Output, ParseVariableError> {
+        match var {
+            Variable::Map(x) => {
+                let mut map = HashMap::new();
+                for (key, var) in x.into_iter() {
+                    map.insert(key, var.try_parse::<T>()?);
+                }
+                Ok(map)
+            }
+            _ => Err(ParseVariableError::new("HashMap<String, T>")),
+        }
+    }
+
+    fn from_var_ref(var: &Variable) -> Result<Self::RefOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Map(x) => {
+                let mut map = HashMap::new();
+                for (key, var) in x.iter() {
+                    map.insert(key.as_str(), var.try_parse_ref::<T>()?);
+                }
+                Ok(map)
+            }
+            _ => Err(ParseVariableError::new("HashMap<String, T>")),
+        }
+    }
+
+    fn from_var_mut(var: &mut Variable) -> Result<Self::MutOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Map(x) => {
+                let mut map = HashMap::new();
+                for (key, var) in x.iter_mut() {
+                    map.insert(key.as_str(), var.try_parse_mut::<T>()?);
+                }
+                Ok(map)
+            }
+            _ => Err(ParseVariableError::new("HashMap<String, T>")),
+        }
+    }
+}
+
+impl FromVariable for [u8] {
+    type Output = Vec<u8>;
+    type RefOutput<'a> = &'a [u8];
+    type MutOutput<'a> = &'a mut [u8];
+
+    fn from_var(var: Variable) -> Result<Self::Output, ParseVariableError> {
+        match var {
+            Variable::Bytes(x) => Ok(x.to_vec()),
+            _ => Err(ParseVariableError::new("[u8]")),
+        }
+    }
+
+    fn from_var_ref(var: &Variable) -> Result<Self::RefOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Bytes(x) => Ok(x),
+            _ => Err(ParseVariableError::new("[u8]")),
+        }
+    }
+
+    // Изменять можно только буфер, который больше никем не используется
+    fn from_var_mut(var: &mut Variable) -> Result<Self::MutOutput<'_>, ParseVariableError> {
+        match var {
+            Variable::Bytes(x) => Arc::get_mut(x).ok_or(ParseVariableError::new("[u8]")),
+            _ => Err(ParseVariableError::new("[u8]")),
+        }
+    }
+}
+
 impl_from_variable!(i8, I8);
 impl_from_variable!(i16, I16);
 impl_from_variable!(i32, I32);
@@ -280,6 +474,7 @@ impl_from_variable!(f64, F64);
 impl_from_variable!(bool, Bool);
 impl_from_variable!(char, Char);
 impl_from_variable!(String, String);
+impl_from_variable!(Arc<[u8]>, Bytes);
 
 #[test]
 fn into() {
@@ -321,3 +516,54 @@ fn parse_vec() {
 
     assert_eq!(b, vec![&mut 10]);
 }
+
+#[test]
+fn parse_option() {
+    let mut a: Variable = Some(10_i32).into();
+    let b: Variable = None::<i32>.into();
+
+    assert_eq!(a.clone().parse::<Option<i32>>(), Some(10));
+    assert_eq!(a.parse_ref::<Option<i32>>(), Some(&10));
+    *a.parse_mut::<Option<i32>>().unwrap() = 20;
+    assert_eq!(a, Variable::I32(20));
+
+    assert_eq!(b, Variable::Null);
+    assert_eq!(b.parse_ref::<Option<i32>>(), None);
+    assert!(Variable::from("10").try_parse_ref::<Option<i32>>().is_err());
+}
+
+#[test]
+fn parse_map() {
+    let mut a: Variable = HashMap::from([("a".to_string(), 1_i32), ("b".to_string(), 2)]).into();
+
+    assert_eq!(
+        a.clone().parse::<HashMap<String, i32>>(),
+        HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+    );
+    assert_eq!(a.parse_ref::<HashMap<String, i32>>()["b"], &2);
+    *a.parse_mut::<HashMap<String, i32>>().remove("a").unwrap() = 3;
+
+    assert_eq!(
+        a.parse_ref::<BTreeMap<String, Variable>>()["a"],
+        Variable::I32(3)
+    );
+    assert!(a.try_parse_ref::<HashMap<String, String>>().is_err());
+    assert_eq!(a.to_string(), r#"{"a": 3, "b": 2}"#);
+}
+
+#[test]
+fn parse_bytes() {
+    let bytes: Arc<[u8]> = Arc::from(&b"\x00data"[..]);
+    let mut a: Variable = bytes.clone().into();
+
+    assert_eq!(a.parse_ref::<[u8]>(), b"\x00data");
+    assert_eq!(a.clone().parse::<Vec<u8>>(), b"\x00data".to_vec());
+    assert!(Arc::ptr_eq(a.parse_ref::<Arc<[u8]>>(), &bytes));
+    assert!(a.try_parse_mut::<[u8]>().is_err());
+
+    drop(bytes);
+    a.parse_mut::<[u8]>()[0] = b'!';
+
+    assert_eq!(a.to_string(), "b\"!data\"");
+    assert!(a.try_parse_ref::<Vec<u8>>().is_err());
+}
@@ -2,7 +2,9 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
+use super::Variable;
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
 pub enum VariableType {
     #[default]
     Let,
@@ -11,7 +13,12 @@ pub enum VariableType {
     Bool,
     Char,
     String,
-    List,
+    // Тип элементов списка, `Let` - элементы любого типа
+    List(Box<VariableType>),
+    Map,
+    Bytes,
+    // Значение указанного типа или `Null`
+    Optional(Box<VariableType>),
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
@@ -61,9 +68,46 @@ impl VariableType {
     pub const U32: VariableType =
         VariableType::Int(VariableIntType::Unsigned(VariableUnsignedIntType::U32));
     pub const U64: VariableType =
-        VariableType::Int(VariableIntType::Unsigned(VariableUnsignedIntType::U16));
+        VariableType::Int(VariableIntType::Unsigned(VariableUnsignedIntType::U64));
     pub const F32: VariableType = VariableType::Float(VariableFloatType::F32);
     pub const F64: VariableType = VariableType::Float(VariableFloatType::F64);
+
+    pub fn list(ty: VariableType) -> Self {
+        Self::List(Box::new(ty))
+    }
+
+    pub fn optional(ty: VariableType) -> Self {
+        Self::Optional(Box::new(ty))
+    }
+
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Self::Optional(_))
+    }
+
+    // Подходит ли значение под тип, `Let` принимает любое значение
+    pub fn matches(&self, var: &Variable) -> bool {
+        match (self, var) {
+            (Self::Let, _) | (Self::Optional(_), Variable::Null) => true,
+            (Self::Optional(ty), var) => ty.matches(var),
+            (Self::List(ty), Variable::List(list)) => list.iter().all(|var| ty.matches(var)),
+            (&Self::I8, Variable::I8(_))
+            | (&Self::I16, Variable::I16(_))
+            | (&Self::I32, Variable::I32(_))
+            | (&Self::I64, Variable::I64(_))
+            | (&Self::U8, Variable::U8(_))
+            | (&Self::U16, Variable::U16(_))
+            | (&Self::U32, Variable::U32(_))
+            | (&Self::U64, Variable::U64(_))
+            | (&Self::F32, Variable::F32(_))
+            | (&Self::F64, Variable::F64(_))
+            | (Self::Bool, Variable::Bool(_))
+            | (Self::Char, Variable::Char(_))
+            | (Self::String, Variable::String(_))
+            | (Self::Map, Variable::Map(_))
+            | (Self::Bytes, Variable::Bytes(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Default for VariableIntType {
@@ -77,6 +121,8 @@ impl Display for VariableType {
         match self {
             Self::Int(t) => write!(f, "{t}"),
             Self::Float(t) => write!(f, "{t}"),
+            Self::List(t) => write!(f, "List<{t}>"),
+            Self::Optional(t) => write!(f, "Optional<{t}>"),
             ty => write!(f, "{ty:?}"),
         }
     }
@@ -4,6 +4,10 @@ mod utils;
 mod dependency {
     use std::path::PathBuf;
 
+    use august_plugin_system::{
+        utils::{LoadPluginError, ValidateLoaderError},
+        DependencyGraph,
+    };
     use semver::Version;
 
     use crate::utils::{get_plugin_path, loader_init, VoidPluginManager};
@@ -56,4 +60,234 @@ mod dependency {
             );
         }
     }
+
+    #[test]
+    fn resolve_with_backtracking() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for (id, version) in [("app", "1.0.0"), ("lib", "1.0.0"), ("lib", "2.0.0")] {
+            let path = get_plugin_path(&format!("resolver/{id}"), version, "vpl");
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        loader
+            .load_plugin("app", &Version::parse("1.0.0").unwrap())
+            .unwrap();
+
+        let is_load = |version: &str| {
+            loader
+                .get_plugin("lib", &Version::parse(version).unwrap())
+                .unwrap()
+                .is_load()
+        };
+        assert!(is_load("1.0.0"));
+        assert!(!is_load("2.0.0"));
+    }
+
+    #[test]
+    fn resolve_conflict() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for (id, version) in [
+            ("conflict", "1.0.0"),
+            ("left", "1.0.0"),
+            ("right", "1.0.0"),
+            ("shared", "1.0.0"),
+            ("shared", "2.0.0"),
+        ] {
+            let path = get_plugin_path(&format!("resolver/{id}"), version, "vpl");
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        let error = loader
+            .load_plugin("conflict", &Version::parse("1.0.0").unwrap())
+            .unwrap_err();
+
+        match &error {
+            LoadPluginError::DependencyConflict(_) => {
+                let message = error.to_string();
+                println!("{message}");
+                assert!(message.contains("shared"));
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        assert!(loader.get_plugins().iter().all(|plugin| !plugin.is_load()));
+    }
+
+    fn get_resolver_path(plugins: &[(&str, &str)]) -> Vec<PathBuf> {
+        plugins
+            .iter()
+            .map(|(id, version)| get_plugin_path(&format!("resolver/{id}"), version, "vpl"))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_shared_dependency() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let paths = get_resolver_path(&[
+            ("caret", "1.0.0"),
+            ("exact", "1.0.0"),
+            ("common", "1.0.0"),
+            ("common", "1.1.0"),
+        ]);
+
+        loader
+            .load_plugins(paths.iter().map(|x| x.to_str().unwrap()))
+            .unwrap();
+
+        let is_load = |id: &str, version: &str| {
+            loader
+                .get_plugin(id, &Version::parse(version).unwrap())
+                .unwrap()
+                .is_load()
+        };
+        assert!(is_load("caret", "1.0.0") && is_load("exact", "1.0.0"));
+        assert!(is_load("common", "1.0.0"));
+        assert!(!is_load("common", "1.1.0"));
+    }
+
+    #[test]
+    fn validate_shared_dependency_conflict() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let paths = get_resolver_path(&[
+            ("exact", "1.0.0"),
+            ("newest", "1.0.0"),
+            ("common", "1.0.0"),
+            ("common", "1.1.0"),
+        ]);
+
+        for path in paths {
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        // По отдельности каждый плагин разрешается
+        match loader.validate() {
+            Err(ValidateLoaderError::InvalidPlugins(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert!(matches!(
+                    errors[0].1,
+                    LoadPluginError::DependencyConflict(_)
+                ));
+            }
+            Ok(_) => panic!("Conflict not detected"),
+        }
+    }
+
+    fn get_cycle_path() -> Vec<PathBuf> {
+        vec![
+            get_plugin_path("cycle/cycle_a", "1.0.0", "vpl"),
+            get_plugin_path("cycle/cycle_b", "1.0.0", "vpl"),
+            get_plugin_path("cycle/cycle_c", "1.0.0", "vpl"),
+        ]
+    }
+
+    #[test]
+    fn load_dependency_cycle() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for path in get_cycle_path() {
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        let error = loader
+            .load_plugin("cycle_a", &Version::parse("1.0.0").unwrap())
+            .unwrap_err();
+
+        match error {
+            LoadPluginError::DependencyCycle(cycle) => {
+                let ids: Vec<_> = cycle.iter().map(|bundle| bundle.id.as_str()).collect();
+                assert_eq!(ids, ["cycle_a", "cycle_b", "cycle_c", "cycle_a"]);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        assert!(loader.get_plugins().iter().all(|plugin| !plugin.is_load()));
+    }
+
+    #[test]
+    fn validate_dependency_plugins() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for path in get_dependencys_path() {
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        loader.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_dependency_cycle() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for path in get_dependencys_path().into_iter().chain(get_cycle_path()) {
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        match loader.validate() {
+            Err(ValidateLoaderError::InvalidPlugins(errors)) => {
+                assert_eq!(errors.len(), 3);
+                assert!(errors.iter().all(|(bundle, error)| {
+                    bundle.id.starts_with("cycle_")
+                        && matches!(error, LoadPluginError::DependencyCycle(_))
+                }));
+            }
+            Ok(_) => panic!("Cycle not detected"),
+        }
+    }
+
+    #[test]
+    fn dependency_graph() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        for path in get_dependencys_path() {
+            loader.register_plugin(path.to_str().unwrap()).unwrap();
+        }
+
+        loader
+            .load_plugin("dep_2", &Version::parse("1.0.0").unwrap())
+            .unwrap();
+
+        let graph = loader.dependency_graph();
+        assert_eq!(graph.nodes.len(), 4);
+
+        let is_load = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.bundle.id == id)
+                .unwrap()
+                .is_load
+        };
+        assert!(is_load("dep_1") && is_load("dep_2"));
+        assert!(!is_load("dep_3") && !is_load("dep_4"));
+
+        let edge = |from: &str, to: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|edge| edge.from.id == from && edge.depend.id == to)
+                .unwrap()
+        };
+        assert_eq!(
+            edge("dep_4", "dep_1").resolved.as_ref().unwrap().id,
+            "dep_1"
+        );
+        assert!(!edge("dep_4", "dep_1").optional);
+        assert!(edge("dep_4", "dep_3").optional);
+        assert!(edge("dep_4", "dep_5").resolved.is_none());
+        // Требуется dep_3 версии 2.0.0, а зарегистрирована 1.0.0
+        assert!(edge("dep_2", "dep_3").resolved.is_none());
+
+        let json = graph.to_json().unwrap();
+        assert_eq!(DependencyGraph::from_json(&json).unwrap(), graph);
+
+        let dot = graph.to_dot();
+        println!("{dot}");
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("\"dep_4-v1.0.0.vpl\" -> \"dep_1-v1.0.0.vpl\""));
+        assert!(dot.contains("\"dep_4-v1.0.0.vpl\" -> \"dep_5[^1.0.0]\""));
+    }
 }
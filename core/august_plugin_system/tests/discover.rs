@@ -0,0 +1,114 @@
+mod utils;
+
+#[cfg(all(test, feature = "discover"))]
+mod tests {
+    use august_plugin_system::{utils::DiscoverPluginsError, DiscoverOptions, SkipReason};
+
+    use crate::utils::{loader_init, VoidPluginManager};
+
+    const ROOT: &str = "../../plugins";
+
+    fn names(paths: Vec<&std::path::PathBuf>) -> Vec<String> {
+        paths
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn discover_root() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let report = loader.discover([ROOT], DiscoverOptions::new()).unwrap();
+        assert_eq!(
+            names(report.plugins.iter().map(|(path, _)| path).collect()),
+            vec!["plugin_for_manager-v1.0.0.vpl", "void_plugin-v1.0.0.vpl"]
+        );
+
+        let unknown_formats: Vec<_> = report
+            .skipped
+            .iter()
+            .filter(|(_, reason)| matches!(reason, SkipReason::UnknownFormat(_)))
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            names(unknown_formats),
+            vec!["function_plugin-v1.0.0.fpl", "function_plugin-v2.0.0.fpl"]
+        );
+
+        // Каталоги на пределе глубины не являются бандлами
+        assert!(report.skipped.iter().any(|(path, reason)| {
+            path.ends_with("native_plugin") && matches!(reason, SkipReason::BadName(_))
+        }));
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn discover_recursive() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let options = DiscoverOptions::new()
+            .with_max_depth(2)
+            .with_exclude("native_plugin")
+            .with_registered_formats_only();
+        let report = loader.discover([ROOT], options).unwrap();
+
+        assert_eq!(report.plugins.len(), 27);
+
+        // Бандлы без менеджера попадают в отчёт, а не пропускаются молча
+        assert_eq!(report.skipped.len(), 9, "{:?}", report.skipped);
+        assert!(report
+            .skipped
+            .iter()
+            .all(|(_, reason)| matches!(reason, SkipReason::UnknownFormat(_))));
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn discover_duplicates() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let options = DiscoverOptions::new()
+            .with_max_depth(2)
+            .with_include("dependency/dep_[12]-*");
+        let report = loader.discover([ROOT], options.clone()).unwrap();
+        assert_eq!(
+            report
+                .bundles()
+                .iter()
+                .map(|bundle| bundle.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["dep_1", "dep_2"]
+        );
+
+        let report = loader.discover([ROOT], options).unwrap();
+        assert!(report.plugins.is_empty());
+        assert_eq!(report.skipped.len(), 2);
+        assert!(report
+            .skipped
+            .iter()
+            .all(|(_, reason)| matches!(reason, SkipReason::Duplicate { .. })));
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn discover_errors() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let options = DiscoverOptions::new().with_include("[");
+        assert!(matches!(
+            loader.discover([ROOT], options),
+            Err(DiscoverPluginsError::InvalidPattern(..))
+        ));
+
+        assert!(matches!(
+            loader.discover(["../../plugins/missing"], DiscoverOptions::new()),
+            Err(DiscoverPluginsError::ReadDirFailed(..))
+        ));
+
+        loader.stop().unwrap();
+    }
+}
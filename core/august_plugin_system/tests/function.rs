@@ -4,9 +4,21 @@ extern crate codegen;
 
 #[cfg(test)]
 mod tests {
-    use august_plugin_system::{function::Request, variable::VariableType, Loader};
-    use codegen::function;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use august_plugin_system::{
+        context::LoadPluginContext,
+        function::{Function, FunctionOutput, Request},
+        utils::ManagerResult,
+        variable::{Serde, VariableType},
+        Api, Loader, Manager, RegisterPluginContext, StdInfo,
+    };
+    use codegen::{function, functions, requests};
     use semver::Version;
+    use serde::{Deserialize, Serialize};
 
     use crate::utils::{benchmark, get_plugin_path, LuaPluginManager, VoidPluginManager};
 
@@ -20,6 +32,46 @@ mod tests {
         a - b
     }
 
+    #[function]
+    fn sum(_: (), a: Vec<&i32>) -> i32 {
+        a.into_iter().sum()
+    }
+
+    #[function(name = "sum")]
+    fn sum_text(_: (), a: Vec<&String>) -> i32 {
+        a.len() as i32
+    }
+
+    struct Calculator;
+
+    #[functions]
+    impl Calculator {
+        fn mul(&self, a: &i32, b: &i32) -> i32 {
+            a * b
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Person {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Greeting {
+        name: String,
+        greeting: String,
+    }
+
+    /// Requests of `function_plugin`
+    #[requests]
+    trait FunctionPlugin {
+        /// Multiplies two numbers
+        fn mul(&self, a: i32, b: i32) -> i32;
+        fn echo(&self, message: String) -> String;
+        fn greet(&self, person: Serde<Person>) -> Serde<Greeting>;
+        fn main(&self);
+    }
+
     #[test]
     fn register_function() {
         let mut loader = Loader::new();
@@ -39,6 +91,24 @@ mod tests {
         });
     }
 
+    #[test]
+    fn register_service_functions() {
+        let calculator = Arc::new(Calculator);
+
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_functions(Calculator::functions(&calculator));
+            ctx.register_manager(VoidPluginManager::new()).unwrap();
+        });
+
+        let registry = loader.get_registry();
+        assert_eq!(registry[0].name(), "mul");
+        assert_eq!(
+            registry[0].call(&[2.into(), 3.into()]).unwrap(),
+            Some(6.into())
+        );
+    }
+
     #[test]
     fn register_request() {
         let mut loader = Loader::new();
@@ -94,6 +164,44 @@ mod tests {
         };
     }
 
+    #[test]
+    fn call_map_request() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new(
+                "greet".to_string(),
+                vec![VariableType::Map],
+                Some(VariableType::Map),
+            ));
+            ctx.register_manager(LuaPluginManager::new()).unwrap();
+        });
+
+        let bundle = loader
+            .load_plugin_now(
+                get_plugin_path("function_plugin", "1.0.0", "fpl")
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let person = HashMap::from([("name".to_string(), "Alice")]);
+        let result = loader
+            .get_plugin_by_bundle(&bundle)
+            .unwrap()
+            .call_request("greet", &[person.into()])
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            result.parse::<HashMap<String, String>>(),
+            HashMap::from([
+                ("name".to_string(), "Alice".to_string()),
+                ("greeting".to_string(), "Hello, Alice".to_string()),
+            ])
+        );
+    }
+
     #[test]
     fn common_call() {
         let mut loader = Loader::new();
@@ -236,4 +344,169 @@ mod tests {
             .unwrap()
             .unwrap();
     }
+
+    // Менеджер, который регистрирует заказанную функцию с разными типами списка
+    struct TypedRequestManager {
+        manager: VoidPluginManager,
+        results: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl<'a> Manager<'a, FunctionOutput, StdInfo> for TypedRequestManager {
+        fn format(&self) -> &str {
+            "vpl"
+        }
+
+        fn register_plugin(&mut self, context: RegisterPluginContext) -> ManagerResult<StdInfo> {
+            Manager::<FunctionOutput, _>::register_plugin(&mut self.manager, context)
+        }
+
+        fn load_plugin(
+            &mut self,
+            mut context: LoadPluginContext<'a, '_, FunctionOutput, StdInfo>,
+            api: Api<FunctionOutput, StdInfo>,
+        ) -> ManagerResult<()> {
+            let mut results = self.results.lock().unwrap();
+            match context.register_request(sum_text()) {
+                Ok(_) => results.push("Ok".to_string()),
+                Err(e) => results.push(e.to_string()),
+            }
+            match context.register_request(sum()) {
+                Ok(_) => results.push("Ok".to_string()),
+                Err(e) => results.push(e.to_string()),
+            }
+
+            self.manager.load_plugin(context, api)
+        }
+    }
+
+    #[test]
+    fn register_typed_list_request() {
+        let results = Arc::new(Mutex::new(vec![]));
+        let manager = TypedRequestManager {
+            manager: VoidPluginManager::new(),
+            results: results.clone(),
+        };
+
+        let mut loader = Loader::new();
+        loader
+            .context(move |mut ctx| {
+                ctx.register_request(Request::new(
+                    "sum",
+                    vec![VariableType::list(VariableType::I32)],
+                    Some(VariableType::I32),
+                ));
+                ctx.register_manager(manager)
+            })
+            .unwrap();
+
+        assert_eq!(sum().inputs()[0].ty, VariableType::list(VariableType::I32));
+        assert_eq!(sum_text().inputs()[0].ty.to_string(), "List<String>");
+
+        loader
+            .load_plugin_now(
+                get_plugin_path("void_plugin", "1.0.0", "vpl")
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            *results.lock().unwrap(),
+            vec!["The arguments are set incorrectly", "Ok"]
+        );
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn requests_trait() {
+        // Трейт запросов остаётся доступным для реализации
+        struct Local;
+
+        impl FunctionPlugin for Local {
+            fn mul(&self, a: i32, b: i32) -> i32 {
+                a * b
+            }
+
+            fn echo(&self, message: String) -> String {
+                message
+            }
+
+            fn greet(&self, person: Serde<Person>) -> Serde<Greeting> {
+                Serde(Greeting {
+                    greeting: format!("Hello, {}", person.name),
+                    name: person.into_inner().name,
+                })
+            }
+
+            fn main(&self) {}
+        }
+
+        let plugin = Local;
+        assert_eq!(plugin.mul(8, 3), 24);
+        assert_eq!(plugin.echo("Hello".to_string()), "Hello");
+        assert_eq!(
+            plugin
+                .greet(Serde(Person {
+                    name: "Alice".to_string()
+                }))
+                .into_inner(),
+            Greeting {
+                name: "Alice".to_string(),
+                greeting: "Hello, Alice".to_string()
+            }
+        );
+        plugin.main();
+    }
+
+    #[test]
+    fn typed_call_request() {
+        let requests = FunctionPluginRequests::requests();
+        assert_eq!(requests[0].name, "mul");
+        assert_eq!(
+            requests[0].inputs,
+            vec![VariableType::I32, VariableType::I32]
+        );
+        assert_eq!(
+            requests[0].description,
+            Some("Multiplies two numbers".to_string())
+        );
+        assert_eq!(requests[2].inputs, vec![VariableType::Let]);
+        assert_eq!(requests[3].output, None);
+
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_function(add());
+            ctx.register_function(sub());
+            ctx.register_requests(requests);
+            ctx.register_manager(LuaPluginManager::new()).unwrap();
+        });
+
+        loader
+            .load_plugin_now(
+                get_plugin_path("function_plugin", "1.0.0", "fpl")
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let plugin = FunctionPluginRequests::caller(&loader);
+        assert_eq!(plugin.mul(8, 3).unwrap(), vec![24]);
+        assert_eq!(
+            plugin.echo("Hello".to_string()).unwrap(),
+            vec!["Message v.1.0.0: Hello".to_string()]
+        );
+        assert_eq!(
+            plugin
+                .greet(Serde(Person {
+                    name: "Alice".to_string()
+                }))
+                .unwrap(),
+            vec![Serde(Greeting {
+                name: "Alice".to_string(),
+                greeting: "Hello, Alice".to_string()
+            })]
+        );
+        plugin.main().unwrap();
+    }
 }
@@ -0,0 +1,247 @@
+mod utils;
+
+#[cfg(all(test, feature = "integrity"))]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    };
+
+    use august_plugin_system::{
+        utils::{
+            integrity::MANIFEST_FILE, IntegrityError, IntegrityPolicy, Manifest,
+            RegisterPluginError,
+        },
+        LoaderEvent,
+    };
+
+    use crate::utils::{get_plugin_path, loader_init, VoidPluginManager};
+
+    // Копия бандла, которую можно испортить
+    fn copy_plugin(target: &str, with_manifest: bool) -> PathBuf {
+        let source = get_plugin_path("dependency/dep_1", "1.0.0", "vpl");
+        let path = std::env::temp_dir()
+            .join(target)
+            .join(source.file_name().unwrap());
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::copy(source.join("config.toml"), path.join("config.toml")).unwrap();
+
+        if with_manifest {
+            let manifest = Manifest::from_dir(&path).unwrap();
+            std::fs::write(path.join(MANIFEST_FILE), manifest.to_vec().unwrap()).unwrap();
+        }
+
+        path
+    }
+
+    fn tamper(path: &Path) {
+        let config = path.join("config.toml");
+        let content = std::fs::read_to_string(&config).unwrap();
+        std::fs::write(config, content + "\n# tampered\n").unwrap();
+    }
+
+    #[test]
+    fn enforce_integrity() {
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_integrity_policy(IntegrityPolicy::Enforce);
+
+        let path = copy_plugin("august_enforce_integrity", true);
+        loader.register_plugin(path.to_str().unwrap()).unwrap();
+        loader.stop().unwrap();
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_integrity_policy(IntegrityPolicy::Enforce);
+
+        tamper(&path);
+        match loader.register_plugin(path.to_str().unwrap()) {
+            Err(RegisterPluginError::IntegrityMismatch { file }) => assert_eq!(file, "config.toml"),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        let path = copy_plugin("august_enforce_unsigned", false);
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::IntegrityFailed(
+                IntegrityError::MissingManifest
+            ))
+        ));
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn warn_integrity() {
+        let warnings = Arc::new(Mutex::new(vec![]));
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_integrity_policy(IntegrityPolicy::Warn);
+        loader.subscribe({
+            let warnings = warnings.clone();
+            move |event| {
+                if let LoaderEvent::IntegrityWarning { bundle, error } = event {
+                    warnings.lock().unwrap().push(format!("{bundle}: {error}"));
+                }
+            }
+        });
+
+        let path = copy_plugin("august_warn_integrity", true);
+        tamper(&path);
+        loader.register_plugin(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(
+            *warnings.lock().unwrap(),
+            vec!["dep_1-v1.0.0.vpl: File `config.toml` does not match the manifest"]
+        );
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn integrity_off() {
+        let mut loader = loader_init(VoidPluginManager::new());
+        assert_eq!(loader.integrity_policy(), IntegrityPolicy::Off);
+
+        let path = copy_plugin("august_integrity_off", true);
+        tamper(&path);
+        loader.register_plugin(path.to_str().unwrap()).unwrap();
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn zip_with_manifest() {
+        use august_plugin_system::utils::archive::zip;
+
+        let path = copy_plugin("august_zip_manifest", false);
+        let target_path = std::env::temp_dir().join("august_zip_manifest_bundle");
+        let _ = std::fs::remove_dir_all(&target_path);
+        std::fs::create_dir_all(&target_path).unwrap();
+
+        zip(
+            &path,
+            target_path.to_str().unwrap(),
+            zip::CompressionMethod::Stored,
+            |_| {},
+        )
+        .unwrap();
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_integrity_policy(IntegrityPolicy::Enforce);
+
+        let archive = target_path.join(path.file_name().unwrap());
+        loader.register_plugin(archive.to_str().unwrap()).unwrap();
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn trusted_signer() {
+        use august_plugin_system::utils::signing::{generate_key, sign_bundle};
+
+        let key = generate_key();
+        let path = copy_plugin("august_trusted_signer", false);
+        sign_bundle(&path, &key).unwrap();
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_trust_store([key.verifying_key()].into_iter().collect());
+        loader.register_plugin(path.to_str().unwrap()).unwrap();
+        loader.stop().unwrap();
+
+        // Подписанный манифест не даёт подменить файлы
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_trust_store([key.verifying_key()].into_iter().collect());
+
+        tamper(&path);
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::IntegrityMismatch { .. })
+        ));
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn untrusted_bundles() {
+        use august_plugin_system::utils::{
+            signing::{generate_key, sign_bundle},
+            TrustStore,
+        };
+
+        let key = generate_key();
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_trust_store(TrustStore::new());
+
+        let path = copy_plugin("august_unsigned", true);
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::Unsigned)
+        ));
+
+        let path = copy_plugin("august_unknown_signer", false);
+        sign_bundle(&path, &key).unwrap();
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::UnknownSigner { .. })
+        ));
+
+        loader.set_trust_store([key.verifying_key()].into_iter().collect());
+
+        let path = copy_plugin("august_bad_signature", false);
+        sign_bundle(&path, &key).unwrap();
+        let manifest = std::fs::read_to_string(path.join(MANIFEST_FILE)).unwrap();
+        std::fs::write(
+            path.join(MANIFEST_FILE),
+            manifest.replace("config", "Config"),
+        )
+        .unwrap();
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::BadSignature)
+        ));
+
+        assert!(loader.remove_trust_store().is_some());
+        loader.register_plugin(path.to_str().unwrap()).unwrap();
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(all(feature = "archive", feature = "signing"))]
+    #[test]
+    fn zip_signed_bundle() {
+        use august_plugin_system::utils::{
+            archive::zip_signed,
+            signing::{generate_key, SIGNATURE_FILE},
+        };
+
+        let key = generate_key();
+        let path = copy_plugin("august_zip_signed", false);
+        let target_path = std::env::temp_dir().join("august_zip_signed_bundle");
+        let _ = std::fs::remove_dir_all(&target_path);
+        std::fs::create_dir_all(&target_path).unwrap();
+
+        let mut files = vec![];
+        zip_signed(
+            &path,
+            target_path.to_str().unwrap(),
+            zip::CompressionMethod::Stored,
+            &key,
+            |name| files.push(name.to_path_buf()),
+        )
+        .unwrap();
+        assert!(files.contains(&PathBuf::from(SIGNATURE_FILE)));
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_trust_store([key.verifying_key()].into_iter().collect());
+
+        let archive = target_path.join(path.file_name().unwrap());
+        loader.register_plugin(archive.to_str().unwrap()).unwrap();
+
+        loader.stop().unwrap();
+    }
+}
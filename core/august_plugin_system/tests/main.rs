@@ -2,9 +2,17 @@ mod utils;
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Arc, Mutex};
+
     use august_plugin_system::{
-        utils::{UnloadPluginError, UnregisterManagerError, UnregisterPluginError},
-        Loader,
+        context::LoadPluginContext,
+        function::{DynamicFunction, FunctionOutput, Request},
+        utils::{
+            LoadPluginError, ManagerResult, UnloadPluginError, UnregisterManagerError,
+            UnregisterPluginError,
+        },
+        variable::VariableType,
+        Api, Loader, LoaderEvent, Manager, RegisterPluginContext, StdInfo,
     };
 
     use crate::utils::{get_plugin_path, loader_init, LuaPluginManager, VoidPluginManager};
@@ -37,6 +45,7 @@ mod tests {
             plugin.info().path,
             plugin.info().bundle
         );
+        drop(plugin);
 
         loader.unregister_plugin_by_bundle(&bundle).unwrap();
         loader.stop().unwrap();
@@ -107,6 +116,101 @@ mod tests {
         loader.stop().unwrap();
     }
 
+    #[test]
+    fn lifecycle_events() {
+        let events = Arc::new(Mutex::new(vec![]));
+
+        let mut loader = Loader::new();
+        let subscription = loader.subscribe({
+            let events = events.clone();
+            move |event| {
+                let event = match event {
+                    LoaderEvent::ManagerRegistered { format } => format!("manager+ {format}"),
+                    LoaderEvent::ManagerUnregistered { format } => format!("manager- {format}"),
+                    LoaderEvent::IntegrityWarning { bundle, .. } => format!("integrity {bundle}"),
+                    LoaderEvent::PluginRegistered { bundle } => format!("register {bundle}"),
+                    LoaderEvent::PluginLoading { bundle } => format!("loading {bundle}"),
+                    LoaderEvent::PluginLoaded { bundle } => format!("loaded {bundle}"),
+                    LoaderEvent::PluginLoadFailed { bundle, .. } => format!("failed {bundle}"),
+                    LoaderEvent::PluginUnloaded { bundle } => format!("unload {bundle}"),
+                    LoaderEvent::PluginUnregistered { bundle } => format!("unregister {bundle}"),
+                    LoaderEvent::RequestRegistered { bundle, name } => {
+                        format!("request {bundle} {name}")
+                    }
+                };
+                events.lock().unwrap().push(event);
+            }
+        });
+
+        loader.context(|mut ctx| {
+            ctx.register_request(Request::new(
+                "echo".to_string(),
+                vec![VariableType::String],
+                Some(VariableType::String),
+            ));
+            ctx.register_manager(LuaPluginManager::new()).unwrap();
+        });
+
+        let bundle = loader
+            .load_plugin_now(
+                get_plugin_path("function_plugin", "1.0.0", "fpl")
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap();
+        loader.unregister_plugin_by_bundle(&bundle).unwrap();
+        loader.stop().unwrap();
+
+        assert!(loader.unsubscribe(subscription));
+        assert!(!loader.unsubscribe(subscription));
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "manager+ fpl",
+                "register function_plugin-v1.0.0.fpl",
+                "loading function_plugin-v1.0.0.fpl",
+                "request function_plugin-v1.0.0.fpl echo",
+                "loaded function_plugin-v1.0.0.fpl",
+                "unload function_plugin-v1.0.0.fpl",
+                "unregister function_plugin-v1.0.0.fpl",
+                "manager- fpl",
+            ]
+        );
+    }
+
+    #[test]
+    fn load_failed_event() {
+        let failed = Arc::new(Mutex::new(vec![]));
+
+        let mut loader = Loader::<FunctionOutput, StdInfo>::new();
+        loader.subscribe({
+            let failed = failed.clone();
+            move |event| {
+                if let LoaderEvent::PluginLoadFailed { bundle, error } = event {
+                    failed
+                        .lock()
+                        .unwrap()
+                        .push((bundle.to_string(), error.to_string()));
+                }
+            }
+        });
+
+        loader.context(|mut ctx| {
+            ctx.register_request(Request::new("missing".to_string(), vec![], None));
+            ctx.register_manager(VoidPluginManager::new()).unwrap();
+        });
+
+        let path = get_plugin_path("void_plugin", "1.0.0", "vpl");
+        assert!(loader.load_plugin_now(path.to_str().unwrap()).is_err());
+
+        let failed = failed.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "void_plugin-v1.0.0.vpl");
+
+        loader.stop().unwrap();
+    }
+
     #[test]
     fn heavy_load() {
         let mut loader = Loader::new();
@@ -130,4 +234,212 @@ mod tests {
 
         loader.stop().unwrap();
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn concurrent_access() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let paths = vec![
+            get_plugin_path("dependency/dep_1", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_2", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_3", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_4", "1.0.0", "vpl"),
+        ];
+
+        let bundles = loader
+            .par_load_plugins(
+                paths
+                    .iter()
+                    .map(|x| x.to_str().unwrap())
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+
+        // Загрузчик можно читать из нескольких потоков одновременно
+        std::thread::scope(|scope| {
+            for bundle in bundles.iter() {
+                let loader = &loader;
+                scope.spawn(move || {
+                    let plugin = loader.get_plugin_by_bundle(bundle).unwrap();
+                    assert!(plugin.is_load());
+                    assert_eq!(loader.par_get_plugins_by_id(&bundle.id).len(), 1);
+                });
+            }
+        });
+
+        loader.stop().unwrap();
+    }
+
+    // Менеджер, который обращается к загрузчику во время загрузки плагина
+    struct ReentrantManager {
+        manager: VoidPluginManager,
+        errors: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl<'a> Manager<'a, FunctionOutput, StdInfo> for ReentrantManager {
+        fn format(&self) -> &str {
+            "vpl"
+        }
+
+        fn register_plugin(&mut self, context: RegisterPluginContext) -> ManagerResult<StdInfo> {
+            Manager::<FunctionOutput, _>::register_plugin(&mut self.manager, context)
+        }
+
+        fn load_plugin(
+            &mut self,
+            context: LoadPluginContext<'a, '_, FunctionOutput, StdInfo>,
+            api: Api<FunctionOutput, StdInfo>,
+        ) -> ManagerResult<()> {
+            assert!(api.get_plugins().is_none());
+            assert_eq!(api.registry().unwrap().len(), 0);
+
+            let error = api.load_plugin_by_bundle(api.plugin()).unwrap_err();
+            self.errors.lock().unwrap().push(error.to_string());
+
+            self.manager.load_plugin(context, api)
+        }
+    }
+
+    #[test]
+    fn reentrant_api() {
+        let errors = Arc::new(Mutex::new(vec![]));
+        let mut loader = loader_init(ReentrantManager {
+            manager: VoidPluginManager::new(),
+            errors: errors.clone(),
+        });
+
+        loader
+            .load_plugin_now(
+                get_plugin_path("void_plugin", "1.0.0", "vpl")
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![LoadPluginError::LoaderLocked.to_string()]
+        );
+
+        loader.stop().unwrap();
+    }
+
+    // Менеджер, функции плагинов которого обращаются к загрузчику
+    struct CallbackManager {
+        manager: VoidPluginManager,
+    }
+
+    impl<'a> Manager<'a, FunctionOutput, StdInfo> for CallbackManager {
+        fn format(&self) -> &str {
+            "vpl"
+        }
+
+        fn register_plugin(&mut self, context: RegisterPluginContext) -> ManagerResult<StdInfo> {
+            Manager::<FunctionOutput, _>::register_plugin(&mut self.manager, context)
+        }
+
+        fn load_plugin(
+            &mut self,
+            mut context: LoadPluginContext<'a, '_, FunctionOutput, StdInfo>,
+            api: Api<FunctionOutput, StdInfo>,
+        ) -> ManagerResult<()> {
+            context.register_request(DynamicFunction::new("main", vec![], None, move |_| {
+                api.load_plugin_by_bundle(api.plugin())?;
+                Ok(None)
+            }))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reentrant_par_call_request() {
+        let mut loader = loader_init(CallbackManager {
+            manager: VoidPluginManager::new(),
+        });
+        loader.context(|mut ctx| {
+            ctx.register_request(Request::new("main".to_string(), vec![], None))
+        });
+
+        let paths = [
+            get_plugin_path("dependency/dep_1", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_2", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_3", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_4", "1.0.0", "vpl"),
+        ];
+        loader
+            .load_plugins(paths.iter().map(|x| x.to_str().unwrap()))
+            .unwrap();
+
+        // Функции выполняются в пуле rayon, пока вызвавший поток удерживает загрузчик
+        let result = loader.par_call_request("main", &[]).unwrap();
+        assert_eq!(result.len(), 4);
+        for output in result {
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                LoadPluginError::LoaderLocked.to_string()
+            );
+        }
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "archive")]
+    fn zip_plugin(id: &str, version: &str, format: &str, target: &str) -> std::path::PathBuf {
+        use august_plugin_system::utils::archive::zip;
+
+        let target_path = std::env::temp_dir().join(target);
+        std::fs::create_dir_all(&target_path).unwrap();
+
+        let path = get_plugin_path(id, version, format);
+        zip(
+            &path,
+            target_path.to_str().unwrap(),
+            zip::CompressionMethod::Stored,
+            |_| {},
+        )
+        .unwrap();
+
+        target_path.join(path.file_name().unwrap())
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn register_zip_plugin() {
+        let mut loader = loader_init(LuaPluginManager::new());
+
+        let path = zip_plugin("function_plugin", "1.0.0", "fpl", "august_register_zip");
+        let bundle = loader.load_plugin_now(path.to_str().unwrap()).unwrap();
+
+        let cache = loader
+            .get_plugin_by_bundle(&bundle)
+            .unwrap()
+            .info()
+            .path
+            .clone();
+        assert!(cache.join("main.lua").is_file());
+
+        loader.unregister_plugin_by_bundle(&bundle).unwrap();
+        assert!(!cache.exists());
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn stop_zip_plugin() {
+        let mut loader = loader_init(LuaPluginManager::new());
+
+        let path = zip_plugin("function_plugin", "1.0.0", "fpl", "august_stop_zip");
+        let bundle = loader.register_plugin(path.to_str().unwrap()).unwrap();
+        let cache = loader
+            .get_plugin_by_bundle(&bundle)
+            .unwrap()
+            .info()
+            .path
+            .clone();
+
+        loader.stop().unwrap();
+        assert!(!cache.exists());
+    }
 }
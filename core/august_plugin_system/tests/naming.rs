@@ -0,0 +1,105 @@
+mod utils;
+
+#[cfg(all(test, feature = "integrity"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use august_plugin_system::{
+        utils::{
+            integrity::MANIFEST_FILE, BundleFromError, IntegrityPolicy, Manifest,
+            RegisterPluginError,
+        },
+        Bundle, ManifestNaming,
+    };
+
+    use crate::utils::{get_plugin_path, loader_init, VoidPluginManager};
+
+    // Плоский каталог без версии в имени
+    fn flat_plugin(target: &str, bundle: Option<Bundle>) -> PathBuf {
+        let source = get_plugin_path("dependency/dep_1", "1.0.0", "vpl");
+        let path = std::env::temp_dir().join(target).join("nav-vision");
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::copy(source.join("config.toml"), path.join("config.toml")).unwrap();
+
+        let manifest = Manifest {
+            bundle,
+            ..Manifest::from_dir(&path).unwrap()
+        };
+        std::fs::write(path.join(MANIFEST_FILE), manifest.to_vec().unwrap()).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn manifest_naming() {
+        let bundle = Bundle::from_filename("nav-vision-v1.0.0.vpl").unwrap();
+        let path = flat_plugin("august_manifest_naming", Some(bundle.clone()));
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::BundleFromFailed(
+                BundleFromError::FormatFailed { .. }
+            ))
+        ));
+
+        loader.set_bundle_naming(ManifestNaming);
+        loader.set_integrity_policy(IntegrityPolicy::Enforce);
+        assert_eq!(
+            loader.register_plugin(path.to_str().unwrap()).unwrap(),
+            bundle
+        );
+
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn manifest_without_bundle() {
+        let path = flat_plugin("august_manifest_without_bundle", None);
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_bundle_naming(ManifestNaming);
+        assert!(matches!(
+            loader.register_plugin(path.to_str().unwrap()),
+            Err(RegisterPluginError::BundleFromFailed(
+                BundleFromError::ManifestWithoutBundle { .. }
+            ))
+        ));
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn zip_manifest_named_plugin() {
+        use august_plugin_system::utils::archive::zip;
+
+        let bundle = Bundle::from_filename("nav-vision-v1.0.0.vpl").unwrap();
+        let path = flat_plugin("august_zip_manifest_naming", Some(bundle.clone()));
+        let target_path = std::env::temp_dir().join("august_zip_manifest_naming_bundle");
+        let _ = std::fs::remove_dir_all(&target_path);
+        std::fs::create_dir_all(&target_path).unwrap();
+
+        zip(
+            &path,
+            target_path.to_str().unwrap(),
+            zip::CompressionMethod::Stored,
+            |_| {},
+        )
+        .unwrap();
+
+        let mut loader = loader_init(VoidPluginManager::new());
+        loader.set_bundle_naming(ManifestNaming);
+        loader.set_integrity_policy(IntegrityPolicy::Enforce);
+
+        let archive = target_path.join("nav-vision");
+        assert_eq!(
+            loader.register_plugin(archive.to_str().unwrap()).unwrap(),
+            bundle
+        );
+
+        loader.stop().unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+mod utils;
+
+#[cfg(test)]
+mod reload {
+    use std::sync::{Arc, Mutex};
+
+    use august_plugin_system::{LoaderEvent, Subscription};
+    use semver::Version;
+
+    use crate::utils::{get_plugin_path, loader_init, LuaPluginManager, VoidPluginManager};
+
+    #[test]
+    fn reload_plugin_with_dependents() {
+        let mut loader = loader_init(VoidPluginManager::new());
+
+        let paths = vec![
+            get_plugin_path("dependency/dep_1", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_2", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_3", "1.0.0", "vpl"),
+            get_plugin_path("dependency/dep_4", "1.0.0", "vpl"),
+        ];
+        loader
+            .load_plugins(paths.iter().map(|x| x.to_str().unwrap()))
+            .unwrap();
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let _: Subscription = loader.subscribe({
+            let events = events.clone();
+            move |event| match event {
+                LoaderEvent::PluginUnloaded { bundle } => {
+                    events.lock().unwrap().push(format!("unload {}", bundle.id))
+                }
+                LoaderEvent::PluginLoaded { bundle } => {
+                    events.lock().unwrap().push(format!("load {}", bundle.id))
+                }
+                _ => (),
+            }
+        });
+
+        loader
+            .reload_plugin("dep_1", &Version::parse("1.0.0").unwrap())
+            .unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "unload dep_4",
+                "unload dep_3",
+                "unload dep_2",
+                "unload dep_1",
+                "load dep_1",
+                "load dep_2",
+                "load dep_3",
+                "load dep_4",
+            ]
+        );
+        assert!(loader.get_plugins().iter().all(|plugin| plugin.is_load()));
+    }
+
+    #[test]
+    fn failed_manual_reload_leaves_broken_plugin_unloaded() {
+        let root = std::env::temp_dir().join("august_failed_reload_test");
+        let _ = std::fs::remove_dir_all(&root);
+
+        // Плагин `base` и два зависимых от него плагина
+        let plugins = [
+            ("base", ""),
+            ("first", "base = \"1.0.0\""),
+            ("second", "base = \"1.0.0\""),
+        ];
+        for (id, depends) in plugins {
+            let path = root.join(format!("{id}-v1.0.0.fpl"));
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(
+                path.join("config.toml"),
+                format!(
+                    "name = \"{id}\"\ndescription = \"\"\nauthor = \"\"\nlicense = \"MIT\"\n\n\
+                     [depends]\n{depends}\n\n[optional_depends]\n"
+                ),
+            )
+            .unwrap();
+            std::fs::write(path.join("main.lua"), "return {}").unwrap();
+        }
+
+        let mut loader = loader_init(LuaPluginManager::new());
+        let paths: Vec<_> = plugins
+            .iter()
+            .map(|(id, _)| root.join(format!("{id}-v1.0.0.fpl")))
+            .collect();
+        loader
+            .load_plugins(paths.iter().map(|x| x.to_str().unwrap()))
+            .unwrap();
+
+        // Без наблюдателя откатываться не к чему: сломанный плагин остаётся
+        // выгруженным, а остальные загружаются снова
+        std::fs::write(paths[1].join("main.lua"), "function broken(").unwrap();
+        assert!(loader
+            .reload_plugin("base", &Version::parse("1.0.0").unwrap())
+            .is_err());
+
+        let version = Version::parse("1.0.0").unwrap();
+        let is_load = |id| loader.get_plugin(id, &version).unwrap().is_load();
+        assert!(is_load("base"));
+        assert!(!is_load("first"));
+        assert!(is_load("second"));
+
+        loader.stop().unwrap();
+    }
+
+    #[cfg(feature = "hot-reload")]
+    mod hot_reload {
+        use std::{
+            path::PathBuf,
+            sync::{Arc, Mutex},
+            time::{Duration, Instant},
+        };
+
+        use august_plugin_system::{
+            function::{FunctionOutput, Request},
+            variable::{Variable, VariableType},
+            Loader, StdInfo,
+        };
+
+        use crate::utils::{get_plugin_path, LuaPluginManager};
+
+        fn copy_plugin(target: &str) -> PathBuf {
+            let source = get_plugin_path("function_plugin", "1.0.0", "fpl");
+            let path = std::env::temp_dir()
+                .join(target)
+                .join(source.file_name().unwrap());
+
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            for file in ["config.toml", "main.lua"] {
+                std::fs::copy(source.join(file), path.join(file)).unwrap();
+            }
+
+            path
+        }
+
+        fn echo(loader: &Loader<'static, FunctionOutput, StdInfo>) -> Variable {
+            let result = loader.call_request("echo", &["August".into()]).unwrap();
+            result[0].as_ref().unwrap().clone().unwrap()
+        }
+
+        // Ждём, пока наблюдатель заметит изменения
+        fn wait_reload(
+            loader: &mut Loader<'static, FunctionOutput, StdInfo>,
+            reports: &Mutex<Vec<bool>>,
+            expected: bool,
+        ) {
+            let timer = Instant::now();
+            while !reports.lock().unwrap().contains(&expected) {
+                assert!(
+                    timer.elapsed() < Duration::from_secs(5),
+                    "plugin was not reloaded"
+                );
+
+                std::thread::sleep(Duration::from_millis(50));
+                loader.poll_hot_reload().unwrap();
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+            loader.poll_hot_reload().unwrap();
+        }
+
+        #[test]
+        fn reload_changed_plugin() {
+            let path = copy_plugin("august_hot_reload_test");
+
+            let mut loader = Loader::new();
+            loader.context(|mut ctx| {
+                ctx.register_request(Request::new(
+                    "echo".to_string(),
+                    vec![VariableType::String],
+                    Some(VariableType::String),
+                ));
+                ctx.register_manager(LuaPluginManager::new()).unwrap();
+            });
+            loader.load_plugin_now(path.to_str().unwrap()).unwrap();
+
+            let reports = Arc::new(Mutex::new(vec![]));
+            loader
+                .enable_hot_reload({
+                    let reports = reports.clone();
+                    move |_, result| reports.lock().unwrap().push(result.is_ok())
+                })
+                .unwrap();
+
+            // Плагин перезагружается с новым кодом
+            std::fs::write(
+                path.join("main.lua"),
+                "function echo(message) return \"Reloaded: \" .. message end return {}",
+            )
+            .unwrap();
+            wait_reload(&mut loader, &reports, true);
+            assert_eq!(echo(&loader), Variable::String("Reloaded: August".into()));
+
+            // Ошибка откатывает плагин к прошлой версии
+            std::fs::write(path.join("main.lua"), "function echo(").unwrap();
+            wait_reload(&mut loader, &reports, false);
+            assert_eq!(echo(&loader), Variable::String("Reloaded: August".into()));
+
+            loader.stop().unwrap();
+        }
+    }
+}
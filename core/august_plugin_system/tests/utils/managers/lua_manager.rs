@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::PathBuf,
     sync::{Arc, Mutex},
     vec,
@@ -8,7 +8,7 @@ use std::{
 use august_plugin_system::{
     context::LoadPluginContext,
     function::{Arg, DynamicFunction, FunctionOutput},
-    utils::ManagerResult,
+    utils::{LoadPluginError, ManagerResult},
     variable::{Variable, VariableType},
     Api, Bundle, Manager, Plugin, Registry, Requests, StdInfo,
 };
@@ -41,11 +41,13 @@ impl<'a> Manager<'a, FunctionOutput, StdInfo> for LuaPluginManager {
         {
             let lua = &*lua.lock().unwrap();
 
-            self.registry_to_lua(lua, api.registry())?;
+            let registry = api.registry().ok_or(LoadPluginError::LoaderLocked)?;
+            self.registry_to_lua(lua, &registry)?;
             self.register_api(lua, &api)?;
         }
 
-        self.load_src(&lua, api, context.plugin().info().path.clone())?;
+        let path = context.plugin().info().path.clone();
+        self.load_src(&lua, &mut context, path)?;
 
         let requests = self.register_requests(&lua, context.requests())?;
         for request in requests {
@@ -132,7 +134,7 @@ impl LuaPluginManager {
     fn load_src(
         &self,
         lua: &Arc<Mutex<Lua>>,
-        api: Arc<Api<FunctionOutput, StdInfo>>,
+        context: &mut LoadPluginContext<'_, '_, FunctionOutput, StdInfo>,
         path: PathBuf,
     ) -> ManagerResult<()> {
         let arc_lua = lua.lock().unwrap();
@@ -140,7 +142,6 @@ impl LuaPluginManager {
         let src = std::fs::read_to_string(path.join("main.lua"))?;
         let result: Vec<Table> = arc_lua.load(&src).eval()?;
 
-        let plugin = api.get_plugin_mut_by_bundle(api.plugin()).unwrap();
         let global = arc_lua.globals();
 
         for info in result.into_iter() {
@@ -177,7 +178,7 @@ impl LuaPluginManager {
                 },
             );
 
-            plugin.register_function(function)?;
+            context.register_function(function)?;
         }
 
         Ok(())
@@ -200,7 +201,7 @@ impl LuaPluginManager {
                     let request_name = request.name.clone();
                     let lua = lua.clone();
 
-                    let function = DynamicFunction::new(
+                    let mut function = DynamicFunction::new(
                         request.name.clone(),
                         request
                             .inputs
@@ -213,6 +214,7 @@ impl LuaPluginManager {
                             .collect(),
                         request
                             .output
+                            .as_ref()
                             .map(|output| Arg::new("output", output.clone())),
                         move |args| {
                             let request_name = request_name.clone();
@@ -234,6 +236,9 @@ impl LuaPluginManager {
                             result
                         },
                     );
+                    if let Some(description) = &request.description {
+                        function = function.with_description(description);
+                    }
 
                     result.push(function);
                 }
@@ -332,14 +337,29 @@ impl LuaPluginManager {
             )),
             Value::Integer(var) => Ok(Variable::I32(*var as i32)),
             Value::Number(var) => Ok(Variable::F32(*var as f32)),
-            Value::String(var) => Ok(Variable::String(var.to_str()?.to_string())),
-            Value::Table(var) => {
+            // Строка, не являющаяся UTF-8, передаётся как набор байтов
+            Value::String(var) => match var.to_str() {
+                Ok(var) => Ok(Variable::String(var.to_string())),
+                Err(_) => Ok(Variable::Bytes(var.as_bytes().into())),
+            },
+            // Таблица с последовательными индексами - список, иначе - словарь
+            Value::Table(var)
+                if var.raw_len() as usize == var.clone().pairs::<Value, Value>().count() =>
+            {
                 let mut list = vec![];
                 for pair in var.clone().pairs::<Value, Value>() {
                     list.push(Self::lua2august(&pair?.1)?);
                 }
                 Ok(Variable::List(list))
             }
+            Value::Table(var) => {
+                let mut map = BTreeMap::new();
+                for pair in var.clone().pairs::<String, Value>() {
+                    let (key, value) = pair?;
+                    map.insert(key, Self::lua2august(&value)?);
+                }
+                Ok(Variable::Map(map))
+            }
             Value::Function(_) => Err(mlua::Error::RuntimeError(
                 "Неподдерживаемый тип переменной".to_string(),
             )),
@@ -374,6 +394,14 @@ impl LuaPluginManager {
                 .map(|v| Self::august2lua(v, lua))
                 .collect::<mlua::Result<Vec<_>>>()?
                 .into_lua(lua),
+            Variable::Map(var) => {
+                let table = lua.create_table()?;
+                for (key, value) in var.iter() {
+                    table.set(key.as_str(), Self::august2lua(value, lua)?)?;
+                }
+                Ok(Value::Table(table))
+            }
+            Variable::Bytes(var) => lua.create_string(&var[..]).map(Value::String),
         }
     }
 }
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 
 use august_plugin_system::{function::FunctionOutput, Loader, Manager, StdInfo};
 
+#[allow(dead_code)]
 pub fn get_plugin_path(id: &str, version: &str, format: &str) -> PathBuf {
     std::env::current_dir()
         .unwrap()
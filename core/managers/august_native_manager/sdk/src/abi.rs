@@ -0,0 +1,56 @@
+use std::ffi::c_char;
+
+pub use august_plugin_system::variable::{
+    free_variable, FfiBuffer, FfiVariable, FfiVariableData, FfiVariableKind, FfiVariableTag,
+    FfiVariableType,
+};
+
+// Версия ABI, которую ожидает менеджер нативных плагинов.
+pub const ABI_VERSION: u32 = 4;
+
+// Имя точки входа плагина.
+pub const PLUGIN_INIT: &[u8] = b"august_plugin_init\0";
+
+pub const STATUS_OK: i32 = 0;
+pub const STATUS_ARGUMENTS_COUNT: i32 = 1;
+pub const STATUS_ARGUMENT_TYPE: i32 = 2;
+pub const STATUS_PANIC: i32 = 3;
+
+// Вызов функции плагина.
+//
+// Аргументы принадлежат менеджеру. Возвращает `STATUS_OK` и записывает
+// результат в `output`, либо один из кодов ошибки `STATUS_*`.
+// Результат освобождается через `FfiPlugin::free_variable`.
+pub type FfiCall = unsafe extern "C" fn(
+    args: *const FfiVariable,
+    args_len: usize,
+    output: *mut FfiVariable,
+) -> i32;
+
+#[repr(C)]
+pub struct FfiFunction {
+    pub name: *const c_char,
+    pub inputs: *const FfiVariableType,
+    pub inputs_len: usize,
+    pub has_output: bool,
+    pub output: FfiVariableType,
+    pub call: FfiCall,
+}
+
+#[repr(C)]
+pub struct FfiPlugin {
+    pub abi_version: u32,
+    pub functions: *const FfiFunction,
+    pub functions_len: usize,
+    // Освобождение переменных, созданных плагином
+    pub free_variable: unsafe extern "C" fn(var: *mut FfiVariable),
+}
+
+pub type FfiPluginInit = unsafe extern "C" fn() -> *const FfiPlugin;
+
+// Обёртка для хранения таблиц ABI в `static`.
+#[doc(hidden)]
+#[repr(transparent)]
+pub struct Exported<T>(pub T);
+
+unsafe impl<T> Sync for Exported<T> {}
@@ -0,0 +1,106 @@
+use august_plugin_system::variable::{FfiVariableKind, FfiVariableType, Variable};
+
+// Тип, который может пересекать границу нативного плагина.
+pub trait FfiArg: Sized {
+    const TYPE: FfiVariableType;
+    const HAS_VALUE: bool = true;
+
+    fn from_variable(var: &Variable) -> Option<Self>;
+    fn into_variable(self) -> Variable;
+}
+
+macro_rules! impl_ffi_arg {
+    ($ty:ty, $ffi:ident) => {
+        impl FfiArg for $ty {
+            const TYPE: FfiVariableType = FfiVariableType::new(FfiVariableKind::$ffi);
+
+            fn from_variable(var: &Variable) -> Option<Self> {
+                match var {
+                    Variable::$ffi(value) => Some(value.clone()),
+                    _ => None,
+                }
+            }
+
+            fn into_variable(self) -> Variable {
+                Variable::$ffi(self)
+            }
+        }
+    };
+}
+
+impl_ffi_arg!(i8, I8);
+impl_ffi_arg!(i16, I16);
+impl_ffi_arg!(i32, I32);
+impl_ffi_arg!(i64, I64);
+impl_ffi_arg!(u8, U8);
+impl_ffi_arg!(u16, U16);
+impl_ffi_arg!(u32, U32);
+impl_ffi_arg!(u64, U64);
+impl_ffi_arg!(f32, F32);
+impl_ffi_arg!(f64, F64);
+impl_ffi_arg!(bool, Bool);
+impl_ffi_arg!(char, Char);
+impl_ffi_arg!(String, String);
+
+// Тип элементов передаётся менеджеру вместе с типом списка
+impl<T: FfiArg> FfiArg for Vec<T> {
+    const TYPE: FfiVariableType = FfiVariableType::list(&T::TYPE);
+
+    fn from_variable(var: &Variable) -> Option<Self> {
+        match var {
+            Variable::List(list) => list.iter().map(T::from_variable).collect(),
+            _ => None,
+        }
+    }
+
+    fn into_variable(self) -> Variable {
+        Variable::List(self.into_iter().map(T::into_variable).collect())
+    }
+}
+
+// Отсутствующее значение передаётся как `Null`
+impl<T: FfiArg> FfiArg for Option<T> {
+    const TYPE: FfiVariableType = T::TYPE.optional();
+
+    fn from_variable(var: &Variable) -> Option<Self> {
+        match var {
+            Variable::Null => Some(None),
+            var => T::from_variable(var).map(Some),
+        }
+    }
+
+    fn into_variable(self) -> Variable {
+        match self {
+            Some(value) => value.into_variable(),
+            None => Variable::Null,
+        }
+    }
+}
+
+impl FfiArg for Variable {
+    const TYPE: FfiVariableType = FfiVariableType::new(FfiVariableKind::Let);
+
+    fn from_variable(var: &Variable) -> Option<Self> {
+        Some(var.clone())
+    }
+
+    fn into_variable(self) -> Variable {
+        self
+    }
+}
+
+impl FfiArg for () {
+    const TYPE: FfiVariableType = FfiVariableType::new(FfiVariableKind::Let);
+    const HAS_VALUE: bool = false;
+
+    fn from_variable(var: &Variable) -> Option<Self> {
+        match var {
+            Variable::Null => Some(()),
+            _ => None,
+        }
+    }
+
+    fn into_variable(self) -> Variable {
+        Variable::Null
+    }
+}
@@ -0,0 +1,6 @@
+pub mod abi;
+mod arg;
+mod macros;
+
+pub use arg::*;
+pub use august_plugin_system::variable::Variable;
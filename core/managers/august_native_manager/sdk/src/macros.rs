@@ -0,0 +1,96 @@
+// Экспорт функций плагина через точку входа `august_plugin_init`:
+//
+// august_native_sdk::export_plugin! {
+//     fn add(a: i32, b: i32) -> i32 {
+//         a + b
+//     }
+// }
+#[macro_export]
+macro_rules! export_plugin {
+	($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $output:ty)? $body:block)*) => {
+		$(
+			#[allow(non_snake_case)]
+			mod $name {
+				#[allow(unused_imports)]
+				use super::*;
+
+				pub fn function($($arg: $ty),*) -> $crate::__output!($($output)?) $body
+
+				pub const INPUTS: &[$crate::abi::FfiVariableType] = &[$(<$ty as $crate::FfiArg>::TYPE),*];
+
+				pub unsafe extern "C" fn call(
+					args: *const $crate::abi::FfiVariable,
+					args_len: usize,
+					output: *mut $crate::abi::FfiVariable,
+				) -> i32 {
+					if args_len != INPUTS.len() {
+						return $crate::abi::STATUS_ARGUMENTS_COUNT;
+					}
+
+					let args = match args_len {
+						0 => &[],
+						len => ::std::slice::from_raw_parts(args, len),
+					};
+
+					#[allow(unused_mut, unused_variables)]
+					let mut args = args.iter();
+					$(
+						let $arg = match args
+							.next()
+							.unwrap()
+							.to_variable()
+							.ok()
+							.and_then(|var| <$ty as $crate::FfiArg>::from_variable(&var))
+						{
+							Some(value) => value,
+							None => return $crate::abi::STATUS_ARGUMENT_TYPE,
+						};
+					)*
+
+					match ::std::panic::catch_unwind(move || function($($arg),*)) {
+						Ok(result) => {
+							*output = $crate::abi::FfiVariable::from($crate::FfiArg::into_variable(result));
+							$crate::abi::STATUS_OK
+						}
+						Err(_) => $crate::abi::STATUS_PANIC,
+					}
+				}
+			}
+		)*
+
+		const __AUGUST_FUNCTIONS: &[$crate::abi::FfiFunction] = &[$(
+			$crate::abi::FfiFunction {
+				name: concat!(stringify!($name), "\0").as_ptr() as *const ::std::ffi::c_char,
+				inputs: $name::INPUTS.as_ptr(),
+				inputs_len: $name::INPUTS.len(),
+				has_output: <$crate::__output!($($output)?) as $crate::FfiArg>::HAS_VALUE,
+				output: <$crate::__output!($($output)?) as $crate::FfiArg>::TYPE,
+				call: $name::call,
+			}
+		),*];
+
+		static __AUGUST_PLUGIN: $crate::abi::Exported<$crate::abi::FfiPlugin> =
+			$crate::abi::Exported($crate::abi::FfiPlugin {
+				abi_version: $crate::abi::ABI_VERSION,
+				functions: __AUGUST_FUNCTIONS.as_ptr(),
+				functions_len: __AUGUST_FUNCTIONS.len(),
+				free_variable: $crate::abi::free_variable,
+			});
+
+		#[no_mangle]
+		pub extern "C" fn august_plugin_init() -> *const $crate::abi::FfiPlugin {
+			&__AUGUST_PLUGIN.0
+		}
+	};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __output {
+    () => {
+        ()
+    };
+    ($output:ty) => {
+        $output
+    };
+}
@@ -1,5 +1,29 @@
+use august_plugin_system::variable::VariableType;
+
 #[derive(thiserror::Error, Debug)]
 pub enum RegisterPluginError {
     #[error("Does not contain config")]
     DoesNotContainConfig,
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoadPluginError {
+    #[error("Entry point `august_plugin_init` returned null")]
+    NullPlugin,
+    #[error("Unsupported ABI version `{0}`")]
+    UnsupportedAbiVersion(u32),
+    #[error("Function name is not valid UTF-8")]
+    InvalidFunctionName,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CallFunctionError {
+    #[error("Expected {expected} arguments, got {actual}")]
+    ArgumentsCount { expected: usize, actual: usize },
+    #[error("Argument `{index}` cannot be passed as `{ty}`")]
+    ArgumentType { index: usize, ty: VariableType },
+    #[error("Function `{0}` panicked")]
+    Panic(String),
+    #[error("Function `{name}` returned status `{status}`")]
+    Status { name: String, status: i32 },
+}
@@ -1,4 +1,144 @@
-#[no_mangle]
-pub unsafe extern "C" fn add(a: i32, b: i32) -> i32 {
-    a + b
+use std::{ffi::CStr, sync::Arc};
+
+use august_native_sdk::abi::{
+    FfiCall, FfiPlugin, FfiPluginInit, ABI_VERSION, PLUGIN_INIT, STATUS_OK, STATUS_PANIC,
+};
+use august_plugin_system::{
+    function::{Arg, DynamicFunction, FunctionOutput},
+    utils::ManagerResult,
+    variable::{free_variable, FfiVariable, VariableType},
+};
+use libloading::Library;
+
+use crate::error::{CallFunctionError, LoadPluginError};
+
+#[derive(Clone)]
+pub(crate) struct NativeFunction {
+    library: Arc<Library>,
+    pub(crate) name: String,
+    inputs: Vec<VariableType>,
+    output: Option<VariableType>,
+    call: FfiCall,
+    free_variable: unsafe extern "C" fn(*mut FfiVariable),
+}
+
+// Получение функций плагина через точку входа `august_plugin_init`
+pub(crate) unsafe fn load_functions(library: &Arc<Library>) -> ManagerResult<Vec<NativeFunction>> {
+    let init = library.get::<FfiPluginInit>(PLUGIN_INIT)?;
+
+    let plugin: &FfiPlugin = init().as_ref().ok_or(LoadPluginError::NullPlugin)?;
+    if plugin.abi_version != ABI_VERSION {
+        return Err(Box::new(LoadPluginError::UnsupportedAbiVersion(
+            plugin.abi_version,
+        )));
+    }
+
+    let functions = match plugin.functions_len {
+        0 => &[],
+        len => std::slice::from_raw_parts(plugin.functions, len),
+    };
+
+    let mut result = vec![];
+    for function in functions {
+        let name = CStr::from_ptr(function.name)
+            .to_str()
+            .map_err(|_| LoadPluginError::InvalidFunctionName)?
+            .to_string();
+
+        // Типы принадлежат плагину, поэтому сразу копируются
+        let inputs = match function.inputs_len {
+            0 => &[],
+            len => std::slice::from_raw_parts(function.inputs, len),
+        };
+
+        result.push(NativeFunction {
+            library: library.clone(),
+            name,
+            inputs: inputs.iter().map(|ty| ty.to_variable_type()).collect(),
+            output: match function.has_output {
+                true => Some(function.output.to_variable_type()),
+                false => None,
+            },
+            call: function.call,
+            free_variable: plugin.free_variable,
+        });
+    }
+
+    Ok(result)
+}
+
+impl NativeFunction {
+    pub(crate) fn into_function(self) -> DynamicFunction {
+        let NativeFunction {
+            library,
+            name,
+            inputs,
+            output,
+            call,
+            free_variable: free_output,
+        } = self;
+
+        let args = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| Arg::new(format!("arg_{index}"), ty.clone()))
+            .collect();
+        let output_arg = output.as_ref().map(|ty| Arg::new("output", ty.clone()));
+
+        DynamicFunction::new(
+            name.clone(),
+            args,
+            output_arg,
+            move |args| -> FunctionOutput {
+                // Библиотека должна жить, пока существует функция
+                let _library = &library;
+
+                if args.len() != inputs.len() {
+                    return Err(Box::new(CallFunctionError::ArgumentsCount {
+                        expected: inputs.len(),
+                        actual: args.len(),
+                    }));
+                }
+
+                let mismatch = args
+                    .iter()
+                    .zip(inputs.iter())
+                    .position(|(arg, ty)| !ty.matches(arg));
+
+                if let Some(index) = mismatch {
+                    return Err(Box::new(CallFunctionError::ArgumentType {
+                        index,
+                        ty: inputs[index].clone(),
+                    }));
+                }
+
+                let mut ffi_args: Vec<FfiVariable> =
+                    args.iter().cloned().map(FfiVariable::from).collect();
+
+                let mut ffi_output = FfiVariable::NULL;
+                let status = unsafe { call(ffi_args.as_ptr(), ffi_args.len(), &mut ffi_output) };
+
+                // Аргументы созданы менеджером, а результат - плагином
+                let result = unsafe {
+                    ffi_args.iter_mut().for_each(|arg| free_variable(arg));
+
+                    let result = ffi_output.to_variable();
+                    free_output(&mut ffi_output);
+                    result
+                };
+
+                match status {
+                    STATUS_OK => Ok(match output {
+                        Some(_) => Some(result?),
+                        None => None,
+                    }),
+                    STATUS_PANIC => Err(Box::new(CallFunctionError::Panic(name.clone()))),
+                    status => Err(Box::new(CallFunctionError::Status {
+                        name: name.clone(),
+                        status,
+                    })),
+                }
+            },
+        )
+    }
 }
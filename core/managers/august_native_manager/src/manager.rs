@@ -1,9 +1,9 @@
-use std::env::consts::OS;
+use std::{env::consts::OS, sync::Arc};
 
-use crate::{config::NativeConfig, Plugin};
+use crate::{config::NativeConfig, ffi, Plugin};
 use august_plugin_system::{
-    context::LoadPluginContext, utils::ManagerResult, Api, Bundle, Depend, Manager,
-    Plugin as StdPlugin, RegisterPluginContext, StdInfo,
+    context::LoadPluginContext, function::FunctionOutput, utils::ManagerResult, Api, Bundle,
+    Depend, Manager, Plugin as StdPlugin, RegisterPluginContext, StdInfo,
 };
 use libloading::Library;
 
@@ -17,11 +17,11 @@ impl NativePluginManager {
     }
 
     fn remove_plugin(&mut self, bundle: &Bundle) {
-        self.plugins.retain(|plugin| plugin.bundle == *bundle);
+        self.plugins.retain(|plugin| plugin.bundle != *bundle);
     }
 }
 
-impl<'a, O: Send + Sync> Manager<'a, O, StdInfo> for NativePluginManager {
+impl<'a> Manager<'a, FunctionOutput, StdInfo> for NativePluginManager {
     fn format(&self) -> &str {
         "npl"
     }
@@ -54,15 +54,18 @@ impl<'a, O: Send + Sync> Manager<'a, O, StdInfo> for NativePluginManager {
             .push(Plugin::new(context.bundle.clone(), info.clone(), config));
         Ok(info)
     }
-    fn unregister_plugin(&mut self, plugin: &StdPlugin<'a, O, StdInfo>) -> ManagerResult<()> {
+    fn unregister_plugin(
+        &mut self,
+        plugin: &StdPlugin<'a, FunctionOutput, StdInfo>,
+    ) -> ManagerResult<()> {
         self.remove_plugin(&plugin.info().bundle);
         Ok(())
     }
 
     fn load_plugin(
         &mut self,
-        context: LoadPluginContext<'a, '_, O, StdInfo>,
-        _api: Api<O, StdInfo>,
+        mut context: LoadPluginContext<'a, '_, FunctionOutput, StdInfo>,
+        _api: Api<FunctionOutput, StdInfo>,
     ) -> ManagerResult<()> {
         let plugin = context.plugin();
 
@@ -84,7 +87,23 @@ impl<'a, O: Send + Sync> Manager<'a, O, StdInfo> for NativePluginManager {
             )?;
         }
 
-        let bundle = &plugin.info().bundle;
+        let library = Arc::new(library);
+
+        // Регистрация функций плагина
+        let functions = unsafe { ffi::load_functions(&library)? };
+        for function in functions {
+            let is_request = context
+                .requests()
+                .iter()
+                .any(|request| request.name == function.name);
+
+            if is_request {
+                context.register_request(function.clone().into_function())?;
+            }
+            context.register_function(function.into_function())?;
+        }
+
+        let bundle = &context.plugin().info().bundle;
         self.plugins
             .iter_mut()
             .find(|p| p.bundle == *bundle)
@@ -94,7 +113,10 @@ impl<'a, O: Send + Sync> Manager<'a, O, StdInfo> for NativePluginManager {
         Ok(())
     }
 
-    fn unload_plugin(&mut self, plugin: &StdPlugin<'a, O, StdInfo>) -> ManagerResult<()> {
+    fn unload_plugin(
+        &mut self,
+        plugin: &StdPlugin<'a, FunctionOutput, StdInfo>,
+    ) -> ManagerResult<()> {
         let bundle = &plugin.info().bundle;
         self.plugins
             .iter_mut()
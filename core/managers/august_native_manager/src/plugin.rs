@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use august_plugin_system::{Bundle, StdInfo};
 use libloading::Library;
 
@@ -9,7 +11,7 @@ pub struct Plugin {
     pub(crate) info: StdInfo,
     #[allow(dead_code)]
     pub(crate) config: NativeConfig,
-    pub(crate) library: Option<Library>,
+    pub(crate) library: Option<Arc<Library>>,
 }
 
 impl Plugin {
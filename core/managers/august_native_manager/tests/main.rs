@@ -2,6 +2,13 @@ mod utils;
 
 #[cfg(test)]
 mod main {
+    use august_native_manager::NativePluginManager;
+    use august_plugin_system::{
+        function::Request,
+        variable::{Variable, VariableType},
+        Loader,
+    };
+
     use crate::utils::{get_plugin_path, loader_init};
 
     #[test]
@@ -17,4 +24,119 @@ mod main {
             .load_plugin_now(get_plugin_path("native_plugin", "1.0.0").to_str().unwrap())
             .unwrap();
     }
+
+    #[test]
+    fn call_function() {
+        let mut loader = loader_init();
+
+        let plugin = loader
+            .load_plugin_now(get_plugin_path("native_plugin", "1.0.0").to_str().unwrap())
+            .map(|bundle| loader.get_plugin_by_bundle(&bundle).unwrap())
+            .unwrap();
+
+        let result = plugin.call_function("add", &[2.into(), 3.into()]).unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::I32(5)));
+
+        let result = plugin
+            .call_function("is_positive", &[(-1.0_f64).into()])
+            .unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::Bool(false)));
+
+        let result = plugin.call_function("greet", &["August".into()]).unwrap();
+        assert_eq!(
+            result.unwrap(),
+            Some(Variable::String("Hello, August!".into()))
+        );
+
+        let result = plugin
+            .call_function("sum", &[vec![1, 2, 3].into()])
+            .unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::I32(6)));
+
+        let result = plugin
+            .call_function("total", &[vec![1, 2, 3].into()])
+            .unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::I32(6)));
+
+        let result = plugin.call_function("welcome", &[]).unwrap();
+        assert_eq!(
+            result.unwrap(),
+            Some(Variable::String("Welcome, guest!".into()))
+        );
+
+        let result = plugin.call_function("welcome", &["August".into()]).unwrap();
+        assert_eq!(
+            result.unwrap(),
+            Some(Variable::String("Welcome, August!".into()))
+        );
+
+        let result = plugin.call_function("main", &[]).unwrap();
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn call_function_with_wrong_arguments() {
+        let mut loader = loader_init();
+
+        let plugin = loader
+            .load_plugin_now(get_plugin_path("native_plugin", "1.0.0").to_str().unwrap())
+            .map(|bundle| loader.get_plugin_by_bundle(&bundle).unwrap())
+            .unwrap();
+
+        assert!(plugin.call_function("add", &[2.into()]).unwrap().is_err());
+        assert!(plugin
+            .call_function("add", &["2".into(), 3.into()])
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn call_request() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new(
+                "sub",
+                vec![VariableType::I32, VariableType::I32],
+                Some(VariableType::I32),
+            ));
+            ctx.register_manager(NativePluginManager::new()).unwrap();
+        });
+
+        loader
+            .load_plugin_now(get_plugin_path("native_plugin", "1.0.0").to_str().unwrap())
+            .unwrap();
+
+        let result = loader.call_request("sub", &[9.into(), 4.into()]).unwrap();
+        assert_eq!(
+            result.into_iter().next().unwrap().unwrap(),
+            Some(Variable::I32(5))
+        );
+    }
+
+    #[test]
+    fn call_typed_list_request() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new(
+                "total",
+                vec![VariableType::list(VariableType::I32)],
+                Some(VariableType::I32),
+            ));
+            ctx.register_manager(NativePluginManager::new()).unwrap();
+        });
+
+        loader
+            .load_plugin_now(get_plugin_path("native_plugin", "1.0.0").to_str().unwrap())
+            .unwrap();
+
+        let result = loader.call_request("total", &[vec![4, 5].into()]).unwrap();
+        assert_eq!(
+            result.into_iter().next().unwrap().unwrap(),
+            Some(Variable::I32(9))
+        );
+
+        // Элементы списка проверяются до вызова плагина
+        let result = loader.call_request("total", &[vec!["4"].into()]).unwrap();
+        assert!(result.into_iter().next().unwrap().is_err());
+    }
 }
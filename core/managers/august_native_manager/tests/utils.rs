@@ -1,4 +1,9 @@
-use std::path::PathBuf;
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX, OS},
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+};
 
 use august_native_manager::NativePluginManager;
 use august_plugin_system::{function::FunctionOutput, Loader, StdInfo};
@@ -12,7 +17,53 @@ pub fn loader_init<'a>() -> Loader<'a, FunctionOutput, StdInfo> {
 }
 
 pub fn get_plugin_path(id: &str, version: &str) -> PathBuf {
+    pack_plugin(id, version);
+
     std::env::current_dir()
         .unwrap()
         .join(format!("../../../plugins/{id}/build/{id}-v{version}.npl"))
 }
+
+// Сборка бандла так же, как это делает задача `pack` в Makefile.toml.
+// Каждый бандл собирается один раз, тесты ждут окончания его сборки.
+fn pack_plugin(id: &str, version: &str) {
+    static PACKED: Mutex<Vec<PathBuf>> = Mutex::new(vec![]);
+
+    let plugin_path = std::env::current_dir()
+        .unwrap()
+        .join(format!("../../../plugins/{id}"));
+    let bundle_path = plugin_path.join(format!("build/{id}-v{version}.npl"));
+
+    let mut packed = PACKED.lock().unwrap();
+    if packed.contains(&bundle_path) {
+        return;
+    }
+
+    // target/<profile>/deps/<test> -> target/<profile>
+    let target_path = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|path| path.parent())
+        .unwrap()
+        .to_path_buf();
+    let library = target_path.join(format!("{DLL_PREFIX}{id}{DLL_SUFFIX}"));
+    assert!(
+        library.exists(),
+        "`{}` is missing, build the workspace first",
+        library.display()
+    );
+
+    fs::create_dir_all(bundle_path.join(OS)).unwrap();
+    fs::copy(
+        plugin_path.join(format!("{id}-v{version}.npl/config.toml")),
+        bundle_path.join("config.toml"),
+    )
+    .unwrap();
+    fs::copy(
+        library,
+        bundle_path.join(format!("{OS}/{DLL_PREFIX}main{DLL_SUFFIX}")),
+    )
+    .unwrap();
+
+    packed.push(bundle_path);
+}
@@ -0,0 +1,29 @@
+use wasmi::core::ValueType;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RegisterPluginError {
+    #[error("Does not contain config")]
+    DoesNotContainConfig,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoadPluginError {
+    #[error("Does not contain module `main.wasm`")]
+    DoesNotContainModule,
+    #[error("Import `{module}::{name}` is not found in the registry")]
+    UnresolvedImport { module: String, name: String },
+    #[error("Import `{0}` does not match the signature of the registry function")]
+    ImportSignatureMismatch(String),
+    #[error("Function `{0}` has a signature that cannot be represented")]
+    UnsupportedSignature(String),
+    #[error("Wasm type `{0:?}` is not supported")]
+    UnsupportedType(ValueType),
+    #[error("The loader registry is locked by the current thread")]
+    RegistryLocked,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CallFunctionError {
+    #[error("Expected {expected} arguments, got {actual}")]
+    ArgumentsCount { expected: usize, actual: usize },
+}
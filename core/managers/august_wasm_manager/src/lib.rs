@@ -1,4 +1,9 @@
-#[allow(dead_code)]
-fn main() {
-    println!("Hello, world!");
-}
+mod config;
+pub mod error;
+mod manager;
+mod plugin;
+mod value;
+
+pub use config::*;
+pub use manager::*;
+pub use plugin::*;
@@ -0,0 +1,282 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    error::{CallFunctionError, LoadPluginError},
+    value::{to_variable, to_wasm, variable_type},
+    Plugin, WasmConfig,
+};
+use august_plugin_system::{
+    context::LoadPluginContext,
+    function::{Arg, DynamicFunction, FunctionOutput},
+    utils::ManagerResult,
+    variable::{Variable, VariableType},
+    Api, Bundle, Depend, Manager, Plugin as StdPlugin, RegisterPluginContext, Registry, StdInfo,
+};
+use wasmi::{
+    core::Trap, Engine, ExternType, Func, FuncType, Instance, Linker, Module, Store, Value,
+};
+
+pub struct WasmPluginManager {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+impl WasmPluginManager {
+    pub fn new() -> Self {
+        Self {
+            engine: Engine::default(),
+            plugins: vec![],
+        }
+    }
+
+    fn remove_plugin(&mut self, bundle: &Bundle) {
+        self.plugins.retain(|plugin| plugin.bundle != *bundle);
+    }
+
+    // Связывание импортов модуля с функциями из реестра
+    fn link_registry(
+        linker: &mut Linker<()>,
+        module: &Module,
+        registry: &Registry<FunctionOutput>,
+    ) -> ManagerResult<()> {
+        for import in module.imports() {
+            let ty = match import.ty() {
+                ExternType::Func(ty) => ty,
+                _ => continue,
+            };
+
+            let function = registry
+                .iter()
+                .find(|function| import.module() == "env" && function.name() == import.name())
+                .ok_or_else(|| LoadPluginError::UnresolvedImport {
+                    module: import.module().to_string(),
+                    name: import.name().to_string(),
+                })?;
+
+            let (inputs, output) = Self::signature(import.name(), ty)?;
+
+            let is_compatible = |expected: &VariableType, actual: &VariableType| {
+                *expected == VariableType::Let || expected == actual
+            };
+            let inputs_match = function.inputs().len() == inputs.len()
+                && function
+                    .inputs()
+                    .iter()
+                    .zip(inputs.iter())
+                    .all(|(arg, input)| is_compatible(&arg.ty, &input.ty));
+            let output_match = match (function.output(), output) {
+                (Some(expected), Some(actual)) => is_compatible(&expected.ty, &actual.ty),
+                (None, None) => true,
+                _ => false,
+            };
+
+            if !inputs_match || !output_match {
+                return Err(
+                    LoadPluginError::ImportSignatureMismatch(import.name().to_string()).into(),
+                );
+            }
+
+            let function = function.clone();
+            linker.func_new(
+                import.module(),
+                import.name(),
+                ty.clone(),
+                move |_, params, results| {
+                    let args: Vec<Variable> = params.iter().map(to_variable).collect();
+                    let output = function.call(&args).map_err(|e| Trap::new(e.to_string()))?;
+
+                    match (output, results.first_mut()) {
+                        (Some(var), Some(result)) => {
+                            *result =
+                                to_wasm(&var, result.ty()).map_err(|e| Trap::new(e.to_string()))?
+                        }
+                        (None, None) => (),
+                        _ => return Err(Trap::new("function returned an unexpected result")),
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn signature(name: &str, ty: &FuncType) -> Result<(Vec<Arg>, Option<Arg>), LoadPluginError> {
+        let inputs = ty
+            .params()
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| Ok(Arg::new(format!("arg_{index}"), variable_type(ty)?)))
+            .collect::<Result<Vec<_>, LoadPluginError>>()?;
+
+        let output = match ty.results() {
+            [] => None,
+            [ty] => Some(Arg::new("output", variable_type(ty)?)),
+            _ => return Err(LoadPluginError::UnsupportedSignature(name.to_string())),
+        };
+
+        Ok((inputs, output))
+    }
+
+    // Обёртка экспортируемой функции модуля
+    fn export_function(
+        store: &Arc<Mutex<Store<()>>>,
+        instance: Instance,
+        name: &str,
+    ) -> ManagerResult<DynamicFunction> {
+        let (func, ty) = {
+            let store = store.lock().unwrap();
+            let func = instance
+                .get_func(&*store, name)
+                .ok_or_else(|| LoadPluginError::UnsupportedSignature(name.to_string()))?;
+            (func, func.ty(&*store))
+        };
+
+        let (inputs, output) = Self::signature(name, &ty)?;
+        let store = store.clone();
+
+        Ok(DynamicFunction::new(
+            name,
+            inputs,
+            output,
+            move |args| -> FunctionOutput { Self::call(&store, func, &ty, args) },
+        ))
+    }
+
+    fn call(
+        store: &Mutex<Store<()>>,
+        func: Func,
+        ty: &FuncType,
+        args: &[Variable],
+    ) -> FunctionOutput {
+        if args.len() != ty.params().len() {
+            return Err(Box::new(CallFunctionError::ArgumentsCount {
+                expected: ty.params().len(),
+                actual: args.len(),
+            }));
+        }
+
+        let inputs = args
+            .iter()
+            .zip(ty.params().iter())
+            .map(|(arg, ty)| to_wasm(arg, *ty))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut outputs: Vec<Value> = ty.results().iter().map(|ty| Value::default(*ty)).collect();
+
+        func.call(&mut *store.lock().unwrap(), &inputs, &mut outputs)?;
+
+        Ok(outputs.first().map(to_variable))
+    }
+}
+
+impl Default for WasmPluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Manager<'a, FunctionOutput, StdInfo> for WasmPluginManager {
+    fn format(&self) -> &str {
+        "wpl"
+    }
+
+    fn register_plugin(&mut self, context: RegisterPluginContext) -> ManagerResult<StdInfo> {
+        let config = WasmConfig::load(context.path)?;
+        let info = StdInfo {
+            depends: config.depends.clone().map_or(vec![], |depends| {
+                depends
+                    .into_iter()
+                    .map(|(id, version)| Depend::new(id, version))
+                    .collect()
+            }),
+            optional_depends: config.optional_depends.clone().map_or(vec![], |depends| {
+                depends
+                    .into_iter()
+                    .map(|(id, version)| Depend::new(id, version))
+                    .collect()
+            }),
+        };
+
+        self.plugins
+            .push(Plugin::new(context.bundle.clone(), info.clone(), config));
+        Ok(info)
+    }
+
+    fn unregister_plugin(
+        &mut self,
+        plugin: &StdPlugin<'a, FunctionOutput, StdInfo>,
+    ) -> ManagerResult<()> {
+        self.remove_plugin(&plugin.info().bundle);
+        Ok(())
+    }
+
+    fn load_plugin(
+        &mut self,
+        mut context: LoadPluginContext<'a, '_, FunctionOutput, StdInfo>,
+        api: Api<FunctionOutput, StdInfo>,
+    ) -> ManagerResult<()> {
+        // Загрузка модуля
+        let path = context.plugin().info().path.join("main.wasm");
+        if !path.is_file() {
+            return Err(Box::new(LoadPluginError::DoesNotContainModule));
+        }
+
+        let module = Module::new(&self.engine, std::fs::read(path)?.as_slice())?;
+
+        let mut linker = Linker::new(&self.engine);
+        let registry = api.registry().ok_or(LoadPluginError::RegistryLocked)?;
+        Self::link_registry(&mut linker, &module, &registry)?;
+
+        let mut store = Store::new(&self.engine, ());
+        let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+        let store = Arc::new(Mutex::new(store));
+
+        // Регистрация экспортируемых функций
+        let exports: Vec<String> = module
+            .exports()
+            .filter(|export| export.ty().func().is_some())
+            .map(|export| export.name().to_string())
+            .collect();
+
+        for name in exports.iter() {
+            context.register_function(Self::export_function(&store, instance, name)?)?;
+        }
+
+        // Регистрация заказываемых функций
+        let requests: Vec<String> = context
+            .requests()
+            .iter()
+            .filter(|request| exports.contains(&request.name))
+            .map(|request| request.name.clone())
+            .collect();
+
+        for name in requests.iter() {
+            context.register_request(Self::export_function(&store, instance, name)?)?;
+        }
+
+        let bundle = &context.plugin().info().bundle;
+        self.plugins
+            .iter_mut()
+            .find(|p| p.bundle == *bundle)
+            .unwrap()
+            .store = Some(store);
+
+        Ok(())
+    }
+
+    fn unload_plugin(
+        &mut self,
+        plugin: &StdPlugin<'a, FunctionOutput, StdInfo>,
+    ) -> ManagerResult<()> {
+        let bundle = &plugin.info().bundle;
+        self.plugins
+            .iter_mut()
+            .find(|p| p.bundle == *bundle)
+            .unwrap()
+            .store
+            .take();
+
+        Ok(())
+    }
+}
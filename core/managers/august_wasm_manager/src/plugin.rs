@@ -0,0 +1,26 @@
+use std::sync::{Arc, Mutex};
+
+use august_plugin_system::{Bundle, StdInfo};
+use wasmi::Store;
+
+use crate::WasmConfig;
+
+pub struct Plugin {
+    pub(crate) bundle: Bundle,
+    #[allow(dead_code)]
+    pub(crate) info: StdInfo,
+    #[allow(dead_code)]
+    pub(crate) config: WasmConfig,
+    pub(crate) store: Option<Arc<Mutex<Store<()>>>>,
+}
+
+impl Plugin {
+    pub fn new(bundle: Bundle, info: StdInfo, config: WasmConfig) -> Self {
+        Self {
+            bundle,
+            info,
+            config,
+            store: None,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use august_plugin_system::{
+    utils::ParseVariableError,
+    variable::{Variable, VariableType},
+};
+use wasmi::{
+    core::{ValueType, F32, F64},
+    Value,
+};
+
+use crate::error::LoadPluginError;
+
+pub(crate) fn variable_type(ty: &ValueType) -> Result<VariableType, LoadPluginError> {
+    match ty {
+        ValueType::I32 => Ok(VariableType::I32),
+        ValueType::I64 => Ok(VariableType::I64),
+        ValueType::F32 => Ok(VariableType::F32),
+        ValueType::F64 => Ok(VariableType::F64),
+        ty => Err(LoadPluginError::UnsupportedType(*ty)),
+    }
+}
+
+pub(crate) fn to_wasm(var: &Variable, ty: ValueType) -> Result<Value, ParseVariableError> {
+    match ty {
+        ValueType::I32 => Ok(Value::I32(*var.try_parse_ref::<i32>()?)),
+        ValueType::I64 => Ok(Value::I64(*var.try_parse_ref::<i64>()?)),
+        ValueType::F32 => Ok(Value::F32(F32::from_float(*var.try_parse_ref::<f32>()?))),
+        ValueType::F64 => Ok(Value::F64(F64::from_float(*var.try_parse_ref::<f64>()?))),
+        ValueType::FuncRef => Err(ParseVariableError::new("FuncRef")),
+        ValueType::ExternRef => Err(ParseVariableError::new("ExternRef")),
+    }
+}
+
+pub(crate) fn to_variable(value: &Value) -> Variable {
+    match value {
+        Value::I32(v) => Variable::I32(*v),
+        Value::I64(v) => Variable::I64(*v),
+        Value::F32(v) => Variable::F32(v.to_float()),
+        Value::F64(v) => Variable::F64(v.to_float()),
+        Value::FuncRef(_) | Value::ExternRef(_) => Variable::Null,
+    }
+}
@@ -0,0 +1,169 @@
+mod utils;
+
+#[cfg(test)]
+mod main {
+    use august_plugin_system::{
+        function::Request,
+        utils::LoadPluginError,
+        variable::{Variable, VariableType},
+        Loader,
+    };
+    use august_wasm_manager::{error, WasmPluginManager};
+    use codegen::function;
+    use semver::Version;
+
+    use crate::utils::{get_plugin_path, loader_init};
+
+    #[function]
+    fn add(_: (), a: &i32, b: &i32) -> i32 {
+        a + b
+    }
+
+    #[test]
+    fn load_manager() {
+        loader_init();
+    }
+
+    #[test]
+    fn load_plugin() {
+        let mut loader = loader_init();
+
+        let bundle = loader
+            .load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap())
+            .unwrap();
+
+        loader.unload_plugin_by_bundle(&bundle).unwrap();
+        loader.stop().unwrap();
+    }
+
+    #[test]
+    fn call_function() {
+        let mut loader = loader_init();
+
+        let plugin = loader
+            .load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap())
+            .map(|bundle| loader.get_plugin_by_bundle(&bundle).unwrap())
+            .unwrap();
+
+        let result = plugin.call_function("mul", &[3.into(), 4.into()]).unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::I32(12)));
+
+        let result = plugin.call_function("square", &[1.5_f64.into()]).unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::F64(2.25)));
+
+        let result = plugin.call_function("main", &[]).unwrap();
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn call_function_with_wrong_arguments() {
+        let mut loader = loader_init();
+
+        let plugin = loader
+            .load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap())
+            .map(|bundle| loader.get_plugin_by_bundle(&bundle).unwrap())
+            .unwrap();
+
+        assert!(plugin.call_function("mul", &[3.into()]).unwrap().is_err());
+        assert!(plugin
+            .call_function("mul", &["3".into(), 4.into()])
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn call_request() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new(
+                "mul",
+                vec![VariableType::I32, VariableType::I32],
+                Some(VariableType::I32),
+            ));
+            ctx.register_manager(WasmPluginManager::new()).unwrap();
+        });
+
+        loader
+            .load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap())
+            .unwrap();
+
+        let result = loader.call_request("mul", &[6.into(), 7.into()]).unwrap();
+        assert_eq!(
+            result.into_iter().next().unwrap().unwrap(),
+            Some(Variable::I32(42))
+        );
+    }
+
+    #[test]
+    fn request_with_wrong_signature() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new(
+                "square",
+                vec![VariableType::I32],
+                Some(VariableType::I32),
+            ));
+            ctx.register_manager(WasmPluginManager::new()).unwrap();
+        });
+
+        match loader.load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap()) {
+            Err((None, Some(LoadPluginError::LoadPluginByManager(_)))) => (),
+            r => panic!("Unexpected result: {:?}", r.map_err(|e| e.1)),
+        }
+    }
+
+    #[test]
+    fn request_not_exported() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_request(Request::new("div", vec![], None));
+            ctx.register_manager(WasmPluginManager::new()).unwrap();
+        });
+
+        match loader.load_plugin_now(get_plugin_path("math", "1.0.0").to_str().unwrap()) {
+            Err((None, Some(LoadPluginError::RequestsNotFound(requests)))) => {
+                assert_eq!(requests, vec!["div".to_string()])
+            }
+            r => panic!("Unexpected result: {:?}", r.map_err(|e| e.1)),
+        }
+    }
+
+    #[test]
+    fn call_registry_function() {
+        let mut loader = Loader::new();
+        loader.context(move |mut ctx| {
+            ctx.register_function(add());
+            ctx.register_manager(WasmPluginManager::new()).unwrap();
+        });
+
+        loader
+            .load_plugin_now(get_plugin_path("registry", "1.0.0").to_str().unwrap())
+            .unwrap();
+
+        let plugin = loader
+            .get_plugin("registry", &Version::new(1, 0, 0))
+            .unwrap();
+
+        let result = plugin
+            .call_function("sum", &[1.into(), 2.into(), 3.into()])
+            .unwrap();
+        assert_eq!(result.unwrap(), Some(Variable::I32(6)));
+    }
+
+    #[test]
+    fn unresolved_import() {
+        let mut loader = loader_init();
+
+        match loader.load_plugin_now(get_plugin_path("registry", "1.0.0").to_str().unwrap()) {
+            Err((None, Some(LoadPluginError::LoadPluginByManager(e)))) => {
+                match e.downcast_ref::<error::LoadPluginError>() {
+                    Some(error::LoadPluginError::UnresolvedImport { module, name }) => {
+                        assert_eq!((module.as_str(), name.as_str()), ("env", "add"))
+                    }
+                    _ => panic!("Unexpected error: {e}"),
+                }
+            }
+            r => panic!("Unexpected result: {:?}", r.map_err(|e| e.1)),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use august_plugin_system::{function::FunctionOutput, Loader, StdInfo};
+use august_wasm_manager::WasmPluginManager;
+
+pub fn loader_init<'a>() -> Loader<'a, FunctionOutput, StdInfo> {
+    let mut loader = Loader::new();
+    loader
+        .context(move |mut ctx| ctx.register_manager(WasmPluginManager::new()))
+        .unwrap();
+    loader
+}
+
+pub fn get_plugin_path(id: &str, version: &str) -> PathBuf {
+    std::env::current_dir()
+        .unwrap()
+        .join(format!("../../../plugins/wasm/{id}-v{version}.wpl"))
+}
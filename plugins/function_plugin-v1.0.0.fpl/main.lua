@@ -12,4 +12,8 @@ function echo(message)
 	return "Message v.1.0.0: " .. message;
 end
 
+function greet(person)
+	return { name = person.name, greeting = "Hello, " .. person.name };
+end
+
 return {}
\ No newline at end of file
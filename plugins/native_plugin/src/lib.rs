@@ -1,4 +1,35 @@
-#[allow(dead_code)]
-fn main() {
-    println!("Hello, world!");
+use august_native_sdk::Variable;
+
+august_native_sdk::export_plugin! {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    fn sub(a: i32, b: i32) -> i32 {
+        a - b
+    }
+
+    fn is_positive(x: f64) -> bool {
+        x > 0.0
+    }
+
+    fn greet(name: String) -> String {
+        format!("Hello, {name}!")
+    }
+
+    fn welcome(name: Option<String>) -> String {
+        format!("Welcome, {}!", name.as_deref().unwrap_or("guest"))
+    }
+
+    fn sum(list: Vec<Variable>) -> i32 {
+        list.iter().filter_map(|var| var.try_parse_ref::<i32>().ok()).sum()
+    }
+
+    fn total(list: Vec<i32>) -> i32 {
+        list.iter().sum()
+    }
+
+    fn main() {
+        println!("Hello from native plugin");
+    }
 }
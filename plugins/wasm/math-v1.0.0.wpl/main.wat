@@ -0,0 +1,12 @@
+(module
+	(func (export "mul") (param i32 i32) (result i32)
+		local.get 0
+		local.get 1
+		i32.mul)
+
+	(func (export "square") (param f64) (result f64)
+		local.get 0
+		local.get 0
+		f64.mul)
+
+	(func (export "main")))